@@ -0,0 +1,291 @@
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use crate::dict::IN_MEMORY_BUILD;
+use crate::{
+    Dictionary, DictionaryBuilder, Settings, ValueKind, WordSegmentations,
+    WordSegmentationsWithPayloads,
+};
+
+/// A <code>[Dictionary](crate::Dictionary)</code> whose words can be aliases that are
+/// matched under one spelling and reported under another, such as "colour" reported as
+/// "color".
+///
+/// Each word's payload in the underlying <code>[Dictionary](crate::Dictionary)</code> is
+/// zero for a word that reports itself, or one more than the index of the canonical form
+/// it reports in <code>[canonical_forms](crate::AliasedDictionary::canonical_forms)</code>,
+/// so an <code>[AliasedDictionary](crate::AliasedDictionary)</code> can be saved as the
+/// bytes of its <code>[dictionary](crate::AliasedDictionary::dictionary)</code> along
+/// with its canonical forms and restored with
+/// <code>[from_parts](crate::AliasedDictionary::from_parts)</code>.
+///
+/// # Examples
+///
+/// ```rust
+/// use wordbreaker::{AliasedDictionary, Settings};
+///
+/// let dictionary = AliasedDictionary::new(
+///     ["the", "at"],
+///     [("colour", "color"), ("centre", "center"), ("nite", "night")],
+///     Settings::new(),
+/// );
+///
+/// assert_eq!(
+///     dictionary
+///         .word_segmentations_with_aliases("thecolouratnite")
+///         .collect::<Vec<_>>(),
+///     [vec![
+///         ("the", "the"),
+///         ("colour", "color"),
+///         ("at", "at"),
+///         ("nite", "night")
+///     ]]
+/// );
+/// ```
+#[derive(Clone)]
+pub struct AliasedDictionary<D> {
+    dictionary: Dictionary<D>,
+    canonical_forms: Vec<String>,
+}
+
+impl AliasedDictionary<Vec<u8>> {
+    /// Creates a new <code>[AliasedDictionary](crate::AliasedDictionary)</code> from
+    /// <code>words</code> that report themselves and pairs of <code>aliases</code> and
+    /// the canonical forms they report, canonicalizing the words according to the given
+    /// <code>[Settings](crate::Settings)</code>.
+    ///
+    /// Canonical forms are also added as words that report themselves, and are reported
+    /// exactly as given. When the same word is given as an alias more than once, the
+    /// last canonical form given for it wins, and an alias always wins over a word that
+    /// reports itself.
+    pub fn new<W, A, S, C>(words: W, aliases: A, settings: Settings) -> Self
+    where
+        W: IntoIterator,
+        W::Item: AsRef<str>,
+        A: IntoIterator<Item = (S, C)>,
+        S: AsRef<str>,
+        C: AsRef<str>,
+    {
+        let mut reported = BTreeMap::<String, Option<String>>::new();
+        for word in words {
+            reported
+                .entry(settings.canonicalize(word.as_ref()))
+                .or_insert(None);
+        }
+        for (alias, canonical_form) in aliases {
+            let canonical_form = canonical_form.as_ref();
+            reported
+                .entry(settings.canonicalize(canonical_form))
+                .or_insert(None);
+            reported.insert(
+                settings.canonicalize(alias.as_ref()),
+                Some(canonical_form.to_string()),
+            );
+        }
+
+        let mut canonical_forms = Vec::new();
+        let mut indexes = BTreeMap::new();
+        let mut builder = DictionaryBuilder::memory(settings.with_value_kind(ValueKind::Payloads));
+        let dictionary = reported
+            .into_iter()
+            .try_for_each(|(word, canonical_form)| {
+                let payload = match canonical_form {
+                    Some(canonical_form) => {
+                        let index =
+                            *indexes
+                                .entry(canonical_form)
+                                .or_insert_with_key(|canonical_form| {
+                                    canonical_forms.push(canonical_form.clone());
+                                    canonical_forms.len() - 1
+                                });
+                        (index as u64).wrapping_add(1)
+                    }
+                    None => 0,
+                };
+                builder.insert_weighted(word, payload)
+            })
+            .and_then(|()| builder.into_dictionary())
+            .expect(IN_MEMORY_BUILD);
+
+        Self {
+            dictionary,
+            canonical_forms,
+        }
+    }
+}
+
+impl<D> AliasedDictionary<D>
+where
+    D: AsRef<[u8]>,
+{
+    /// Creates a new <code>[AliasedDictionary](crate::AliasedDictionary)</code> from the
+    /// <code>[dictionary](crate::AliasedDictionary::dictionary)</code> and
+    /// <code>[canonical_forms](crate::AliasedDictionary::canonical_forms)</code> of a
+    /// prior one.
+    ///
+    /// A word whose payload doesn't index into <code>canonical_forms</code> reports
+    /// itself.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::{AliasedDictionary, Dictionary, Settings};
+    ///
+    /// let first = AliasedDictionary::new(["the"], [("centre", "center")], Settings::new());
+    /// let bytes = first.dictionary().as_bytes().to_vec();
+    /// let canonical_forms = first.canonical_forms().to_vec();
+    ///
+    /// let dictionary =
+    ///     AliasedDictionary::from_parts(Dictionary::from_bytes(bytes).unwrap(), canonical_forms);
+    ///
+    /// assert_eq!(dictionary.canonical_form("centre"), Some("center"));
+    /// ```
+    #[inline]
+    pub fn from_parts(dictionary: Dictionary<D>, canonical_forms: Vec<String>) -> Self {
+        Self {
+            dictionary,
+            canonical_forms,
+        }
+    }
+
+    /// Returns the underlying <code>[Dictionary](crate::Dictionary)</code>, whose
+    /// payloads refer to
+    /// <code>[canonical_forms](crate::AliasedDictionary::canonical_forms)</code>.
+    #[inline(always)]
+    pub fn dictionary(&self) -> &Dictionary<D> {
+        &self.dictionary
+    }
+
+    /// Returns the canonical forms that aliases report.
+    #[inline(always)]
+    pub fn canonical_forms(&self) -> &[String] {
+        &self.canonical_forms
+    }
+
+    /// Returns the canonical form that <code>word</code> is an alias of, or
+    /// <code>[None](core::option::Option::None)</code> if it isn't an alias.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::{AliasedDictionary, Settings};
+    ///
+    /// let dictionary = AliasedDictionary::new(["the"], [("nite", "night")], Settings::new());
+    ///
+    /// assert_eq!(dictionary.canonical_form("nite"), Some("night"));
+    /// assert_eq!(dictionary.canonical_form("night"), None);
+    /// assert_eq!(dictionary.canonical_form("the"), None);
+    /// ```
+    #[inline]
+    pub fn canonical_form(&self, word: &str) -> Option<&str> {
+        self.dictionary
+            .payload(word)
+            .and_then(|payload| canonical_form(&self.canonical_forms, payload))
+    }
+
+    /// Returns an <code>[Iterator](core::iter::Iterator)</code> over the ways to
+    /// segment <code>input</code> into words of this
+    /// <code>[AliasedDictionary](crate::AliasedDictionary)</code>, as with
+    /// <code>[Dictionary](crate::Dictionary)::[word_segmentations](crate::Dictionary::word_segmentations)</code>.
+    #[inline]
+    pub fn word_segmentations<'s>(&self, input: &'s str) -> WordSegmentations<'s> {
+        self.dictionary.word_segmentations(input)
+    }
+
+    /// Returns an <code>[Iterator](core::iter::Iterator)</code> over the same
+    /// segmentations as
+    /// <code>[word_segmentations](crate::AliasedDictionary::word_segmentations)</code>,
+    /// but with each word paired with the canonical form it reports, which is the word
+    /// itself unless it is an alias.
+    ///
+    /// The canonical forms are found while <code>input</code> is searched, so no word
+    /// needs to be looked up again.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::{AliasedDictionary, Settings};
+    ///
+    /// let dictionary = AliasedDictionary::new(["ice"], [("nite", "night")], Settings::new());
+    ///
+    /// assert_eq!(
+    ///     dictionary
+    ///         .word_segmentations_with_aliases("niteice")
+    ///         .collect::<Vec<_>>(),
+    ///     [vec![("nite", "night"), ("ice", "ice")]]
+    /// );
+    /// ```
+    #[inline]
+    pub fn word_segmentations_with_aliases<'a>(
+        &'a self,
+        input: &'a str,
+    ) -> WordSegmentationsWithAliases<'a> {
+        WordSegmentationsWithAliases {
+            segmentations: self.dictionary.word_segmentations_with_payloads(input),
+            canonical_forms: &self.canonical_forms,
+        }
+    }
+}
+
+/// Returns the canonical form that <code>payload</code> refers to, if any.
+#[inline]
+fn canonical_form(canonical_forms: &[String], payload: u64) -> Option<&str> {
+    let index = usize::try_from(payload.checked_sub(1)?).ok()?;
+    canonical_forms.get(index).map(String::as_str)
+}
+
+/// The <code>[Iterator](core::iter::Iterator)</code> that
+/// <code>[AliasedDictionary](crate::AliasedDictionary)::[word_segmentations_with_aliases](crate::AliasedDictionary::word_segmentations_with_aliases)</code>
+/// produces.
+///
+/// It yields the same segmentations in the same order as
+/// <code>[WordSegmentations](crate::WordSegmentations)</code>, with each slice of the
+/// input paired with the canonical form it reports.
+#[derive(Clone, Debug)]
+pub struct WordSegmentationsWithAliases<'a> {
+    segmentations: WordSegmentationsWithPayloads<'a>,
+    canonical_forms: &'a [String],
+}
+
+impl<'a> WordSegmentationsWithAliases<'a> {
+    /// Replaces the payload of each word of <code>segmentation</code> with the
+    /// canonical form it refers to.
+    fn with_aliases(&self, segmentation: Vec<(&'a str, u64)>) -> Vec<(&'a str, &'a str)> {
+        segmentation
+            .into_iter()
+            .map(|(word, payload)| {
+                (
+                    word,
+                    canonical_form(self.canonical_forms, payload).unwrap_or(word),
+                )
+            })
+            .collect()
+    }
+}
+
+impl<'a> Iterator for WordSegmentationsWithAliases<'a> {
+    type Item = Vec<(&'a str, &'a str)>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let segmentation = self.segmentations.next()?;
+        Some(self.with_aliases(segmentation))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.segmentations.size_hint()
+    }
+}
+
+impl<'a> DoubleEndedIterator for WordSegmentationsWithAliases<'a> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let segmentation = self.segmentations.next_back()?;
+        Some(self.with_aliases(segmentation))
+    }
+}
+
+impl<'a> core::iter::FusedIterator for WordSegmentationsWithAliases<'a> {}
@@ -0,0 +1,114 @@
+use fst::raw::{Node, Output};
+
+use crate::matcher::{self, WordMatcher};
+use crate::{Dictionary, Settings, SettingsMismatch, WordSegmentations};
+
+/// A <code>[WordMatcher](crate::WordMatcher)</code> for the words of a
+/// <code>[Dictionary](crate::Dictionary)</code> that aren't in a blocklist.
+struct Excluding<'a, D, B> {
+    dictionary: &'a Dictionary<D>,
+    blocklist: &'a Dictionary<B>,
+}
+
+impl<'a, D, B> WordMatcher for Excluding<'a, D, B>
+where
+    D: AsRef<[u8]>,
+    B: AsRef<[u8]>,
+{
+    /// The search states of the dictionary and of the blocklist, which is
+    /// <code>[None](core::option::Option::None)</code> once no blocked word continues
+    /// with the graphemes matched so far.
+    type State = ((Node<'a>, Output), Option<(Node<'a>, Output)>);
+
+    #[inline(always)]
+    fn settings(&self) -> Settings {
+        self.dictionary.settings
+    }
+
+    #[inline]
+    fn start(&self) -> Self::State {
+        (
+            (self.dictionary.fst.root(), Output::zero()),
+            Some((self.blocklist.fst.root(), Output::zero())),
+        )
+    }
+
+    #[inline]
+    fn advance(&self, (search, blocked): &mut Self::State, grapheme: &[u8]) -> bool {
+        if let Some(ref mut blocked_search) = *blocked {
+            if !matcher::advance(self.blocklist, blocked_search, grapheme) {
+                *blocked = None;
+            }
+        }
+
+        matcher::advance(self.dictionary, search, grapheme)
+    }
+
+    #[inline]
+    fn word_end(&self, (search, blocked): &Self::State) -> Option<u64> {
+        match *blocked {
+            Some(ref blocked_search) if matcher::word_end(blocked_search).is_some() => None,
+            _ => matcher::word_end(search),
+        }
+    }
+}
+
+impl<D> Dictionary<D>
+where
+    D: AsRef<[u8]>,
+{
+    /// Finds all segmentations of <code>input</code> into words of this
+    /// <code>[Dictionary](crate::Dictionary)</code> that aren't in
+    /// <code>blocklist</code>, as with
+    /// <code>[word_segmentations](crate::Dictionary::word_segmentations)</code>.
+    ///
+    /// Blocked words are left out while <code>input</code> is searched, before
+    /// segmentations that can't reach the end of <code>input</code> are pruned, so the
+    /// result is the same as for a <code>[Dictionary](crate::Dictionary)</code> without
+    /// those words, including its <code>[count](core::iter::Iterator::count)</code>,
+    /// <code>[nth](core::iter::Iterator::nth)</code> and order. No
+    /// <code>[Dictionary](crate::Dictionary)</code> is rebuilt, so one shared
+    /// <code>[Dictionary](crate::Dictionary)</code> can serve many blocklists.
+    ///
+    /// The words of <code>blocklist</code> are compared without being canonicalized
+    /// again, so it must have the same <code>[Settings](crate::Settings)</code>, apart
+    /// from its <code>[ValueKind](crate::ValueKind)</code>.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::Dictionary;
+    ///
+    /// let dictionary = Dictionary::new(&["just", "ice", "justice"]);
+    /// let blocklist = Dictionary::new(&["justice"]);
+    ///
+    /// assert_eq!(
+    ///     dictionary
+    ///         .word_segmentations_excluding("justice", &blocklist)
+    ///         .unwrap()
+    ///         .collect::<Vec<_>>(),
+    ///     [vec!["just", "ice"]]
+    /// );
+    /// ```
+    pub fn word_segmentations_excluding<'s, B>(
+        &self,
+        input: &'s str,
+        blocklist: &Dictionary<B>,
+    ) -> Result<WordSegmentations<'s>, SettingsMismatch>
+    where
+        B: AsRef<[u8]>,
+    {
+        if !blocklist.settings.canonicalizes_like(&self.settings) {
+            return Err(SettingsMismatch {
+                left: self.settings,
+                right: blocklist.settings,
+            });
+        }
+
+        let matcher = Excluding {
+            dictionary: self,
+            blocklist,
+        };
+        Ok(WordSegmentations::with_payloads(matcher, input, None))
+    }
+}
@@ -0,0 +1,620 @@
+use alloc::collections::BTreeMap;
+#[cfg(feature = "std")]
+use alloc::collections::BinaryHeap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+#[cfg(feature = "std")]
+use core::cmp::Reverse;
+use fst::raw::Fst;
+#[cfg(feature = "std")]
+use std::fs::File;
+#[cfg(feature = "std")]
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+#[cfg(feature = "std")]
+use std::path::PathBuf;
+
+use crate::dict::Body;
+#[cfg(feature = "std")]
+use crate::dict::IN_MEMORY_BUILD;
+use crate::format;
+#[cfg(feature = "std")]
+use crate::format::Fingerprinting;
+use crate::report;
+use crate::{CaseFilter, CaseFolding, Dictionary, Error, Settings, ValueKind, WordFilter};
+#[cfg(not(feature = "std"))]
+use sink::Write;
+
+/// Builds a <code>[Dictionary](crate::Dictionary)</code> by streaming its words
+/// straight into any <code>[Write](std::io::Write)</code>r, without holding every word
+/// in memory at once.
+///
+/// Words can be added in two ways:
+///
+/// * <code>[insert](crate::DictionaryBuilder::insert)</code> and
+///   <code>[extend](crate::DictionaryBuilder::extend)</code> take words that are
+///   already canonicalized according to the builder's
+///   <code>[Settings](crate::Settings)</code> and already sorted by their bytes. They
+///   go directly into the underlying <code>[Fst](fst::raw::Fst)</code> builder.
+/// * <code>[extend_unsorted](crate::DictionaryBuilder::extend_unsorted)</code> takes
+///   words in any order and form. It canonicalizes them, sorts them in runs of a
+///   bounded size, spills each run to a temporary file and merges the runs. Without
+///   the <code>std</code> feature, it sorts all of the words in memory instead.
+///
+/// Each word can also be given a weight, such as its frequency, with the
+/// <code>_weighted</code> variants of these methods. Words added without a weight
+/// weigh zero.
+///
+/// The bytes written are the same as those returned by
+/// <code>[Dictionary](crate::Dictionary)::[as_bytes](crate::Dictionary::as_bytes)</code>,
+/// so they can be loaded with
+/// <code>[Dictionary](crate::Dictionary)::[from_bytes](crate::Dictionary::from_bytes)</code>.
+///
+/// # Examples
+///
+/// ```rust
+/// use wordbreaker::{DictionaryBuilder, Settings};
+///
+/// let mut builder = DictionaryBuilder::memory(Settings::new());
+/// builder.extend_unsorted(&["just", "ice", "justice", "hello"]).unwrap();
+/// let dictionary = builder.into_dictionary().unwrap();
+///
+/// let mut word_segmentations = dictionary
+///     .word_segmentations("justice")
+///     .collect::<Vec<_>>();
+///
+/// word_segmentations.sort_unstable();
+/// assert_eq!(word_segmentations, [vec!["just", "ice"], vec!["justice"]]);
+/// ```
+pub struct DictionaryBuilder<W> {
+    output: Output<W>,
+    settings: Settings,
+    pending: Option<(Vec<u8>, u64)>,
+    max_run_bytes: usize,
+    #[cfg(feature = "std")]
+    spill_dir: Option<PathBuf>,
+    filter: Option<WordFilter>,
+    /// The case-folded form of each word kept so far, with its canonical form, when the
+    /// filter rejects case variants.
+    case_variants: BTreeMap<String, String>,
+    reject_invalid_words: bool,
+}
+
+impl DictionaryBuilder<Vec<u8>> {
+    /// Creates a new <code>[DictionaryBuilder](crate::DictionaryBuilder)</code> that
+    /// writes to memory.
+    #[inline]
+    pub fn memory(settings: Settings) -> Self {
+        let header = format::encode_header(settings).to_vec();
+
+        // Build the `Fst` right after the header, so that its bytes aren't copied.
+        #[cfg(feature = "std")]
+        let output = Output::Streaming(
+            fst::raw::Builder::new(Fingerprinting::new(header)).expect(IN_MEMORY_BUILD),
+        );
+        #[cfg(not(feature = "std"))]
+        let output = Output::Buffered(fst::raw::Builder::memory(), header);
+
+        Self::with_output(output, settings)
+    }
+
+    /// Finishes building and returns the
+    /// <code>[Dictionary](crate::Dictionary)&lt;[Vec](alloc::vec::Vec)&lt;[u8](core::primitive::u8)&gt;&gt;</code>.
+    pub fn into_dictionary(self) -> Result<Dictionary<Vec<u8>>, Error> {
+        let settings = self.settings;
+
+        Ok(Dictionary {
+            fst: Fst::new(Body(self.finish()?))?,
+            settings,
+        })
+    }
+}
+
+impl<W> DictionaryBuilder<W>
+where
+    W: Write,
+{
+    /// The default for
+    /// <code>[with_max_run_bytes](crate::DictionaryBuilder::with_max_run_bytes)</code>.
+    pub const DEFAULT_MAX_RUN_BYTES: usize = 64 << 20;
+
+    /// Creates a new <code>[DictionaryBuilder](crate::DictionaryBuilder)</code> that
+    /// writes to <code>writer</code>, canonicalizing words according to the given
+    /// <code>[Settings](crate::Settings)</code>.
+    pub fn new(mut writer: W, settings: Settings) -> Result<Self, Error> {
+        writer.write_all(&format::encode_header(settings))?;
+
+        #[cfg(feature = "std")]
+        let output = Output::Streaming(fst::raw::Builder::new(Fingerprinting::new(writer))?);
+        #[cfg(not(feature = "std"))]
+        let output = Output::Buffered(fst::raw::Builder::memory(), writer);
+
+        Ok(Self::with_output(output, settings))
+    }
+
+    /// Creates a new <code>[DictionaryBuilder](crate::DictionaryBuilder)</code> whose
+    /// header has already been written to <code>output</code>.
+    fn with_output(output: Output<W>, settings: Settings) -> Self {
+        Self {
+            output,
+            settings,
+            pending: None,
+            max_run_bytes: Self::DEFAULT_MAX_RUN_BYTES,
+            #[cfg(feature = "std")]
+            spill_dir: None,
+            filter: None,
+            case_variants: BTreeMap::new(),
+            reject_invalid_words: false,
+        }
+    }
+
+    /// Sets roughly how many bytes of words
+    /// <code>[extend_unsorted](crate::DictionaryBuilder::extend_unsorted)</code> sorts
+    /// in memory before spilling them to a temporary file. Without the
+    /// <code>std</code> feature, nothing is spilled and this has no effect.
+    #[inline]
+    pub fn with_max_run_bytes(mut self, max_run_bytes: usize) -> Self {
+        self.max_run_bytes = max_run_bytes;
+        self
+    }
+
+    /// Sets the directory that
+    /// <code>[extend_unsorted](crate::DictionaryBuilder::extend_unsorted)</code> spills
+    /// its sorted runs into. By default, this is the system's temporary directory.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn with_spill_dir<P>(mut self, spill_dir: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.spill_dir = Some(spill_dir.into());
+        self
+    }
+
+    /// Skips every word that <code>filter</code> rejects, checking each word as it was
+    /// given to <code>[insert](crate::DictionaryBuilder::insert)</code>,
+    /// <code>[extend_unsorted](crate::DictionaryBuilder::extend_unsorted)</code> or
+    /// their variants, before it is canonicalized.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::{CaseFilter, CaseFolding, DictionaryBuilder, Settings, WordFilter};
+    ///
+    /// let settings = Settings::new().with_case_folding(CaseFolding::Full);
+    /// let mut builder = DictionaryBuilder::memory(settings)
+    ///     .with_filter(WordFilter::new().with_case_filter(CaseFilter::RejectAllUppercase));
+    /// builder.extend_unsorted(["Ice", "ICE", "just"]).unwrap();
+    /// let dictionary = builder.into_dictionary().unwrap();
+    ///
+    /// assert_eq!(dictionary.words().collect::<Vec<_>>(), ["ice", "just"]);
+    /// ```
+    #[inline]
+    pub fn with_filter(mut self, filter: WordFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Makes words that start or end partway through an extended grapheme cluster, which
+    /// could never be found, an
+    /// <code>[Error](crate::Error)::[InvalidWord](crate::Error::InvalidWord)</code>
+    /// instead of being added.
+    #[inline]
+    pub(crate) fn rejecting_invalid_words(mut self) -> Self {
+        self.reject_invalid_words = true;
+        self
+    }
+
+    /// Returns the <code>[Settings](crate::Settings)</code> that this
+    /// <code>[DictionaryBuilder](crate::DictionaryBuilder)</code> was created with.
+    #[inline(always)]
+    pub fn settings(&self) -> Settings {
+        self.settings
+    }
+
+    /// Adds a <code>word</code> that is already canonicalized according to this
+    /// builder's <code>[Settings](crate::Settings)</code>, with a weight of zero.
+    ///
+    /// Words must be added in order of their bytes. Empty words and words rejected by
+    /// the <code>[with_filter](crate::DictionaryBuilder::with_filter)</code> filter are
+    /// skipped and repeats of the previous word are merged, but a word that sorts before
+    /// the previous word is an error.
+    ///
+    /// <b>Note:</b> words are not canonicalized here. A word that is not in canonical
+    /// form will never be found by
+    /// <code>[Dictionary](crate::Dictionary)::[word_segmentations](crate::Dictionary::word_segmentations)</code>.
+    #[inline]
+    pub fn insert<S>(&mut self, word: S) -> Result<(), Error>
+    where
+        S: AsRef<str>,
+    {
+        self.insert_weighted(word, 0)
+    }
+
+    /// Adds a <code>word</code> with a <code>weight</code>, such as its frequency, as
+    /// with <code>[insert](crate::DictionaryBuilder::insert)</code>.
+    ///
+    /// When the same word is added more than once, its weights are added together,
+    /// saturating at <code>[u64::MAX](core::primitive::u64::MAX)</code>. If this
+    /// builder's <code>[Settings](crate::Settings)</code> have a
+    /// <code>[ValueKind](crate::ValueKind)</code> of
+    /// <code>[Payloads](crate::ValueKind::Payloads)</code>, its payloads must be the same
+    /// instead, and different ones are an
+    /// <code>[Error](crate::Error)::[ConflictingPayloads](crate::Error::ConflictingPayloads)</code>.
+    #[inline]
+    pub fn insert_weighted<S>(&mut self, word: S, weight: u64) -> Result<(), Error>
+    where
+        S: AsRef<str>,
+    {
+        let word = word.as_ref();
+        if !self.accepts(word) {
+            return Ok(());
+        }
+        self.check(word)?;
+
+        self.insert_bytes(word.as_bytes(), weight)
+    }
+
+    /// Returns whether <code>word</code> passes the filter, if there is one.
+    #[inline]
+    fn accepts(&mut self, word: &str) -> bool {
+        let filter = match self.filter {
+            Some(ref filter) => filter,
+            None => return true,
+        };
+        if !filter.accepts(word) {
+            return false;
+        }
+        if filter.case_filter() != CaseFilter::RejectCaseVariants {
+            return true;
+        }
+
+        let canonical = self.settings.canonicalize(word);
+        let case_folded = self
+            .settings
+            .with_case_folding(CaseFolding::Full)
+            .canonicalize(word);
+        *self
+            .case_variants
+            .entry(case_folded)
+            .or_insert_with(|| canonical.clone())
+            == canonical
+    }
+
+    /// Checks that <code>word</code> is valid, if invalid words are rejected.
+    #[inline]
+    fn check(&self, word: &str) -> Result<(), Error> {
+        if self.reject_invalid_words && report::has_partial_grapheme(word) {
+            return Err(Error::InvalidWord {
+                word: word.to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Adds the bytes of a <code>word</code> with a <code>weight</code>, as with
+    /// <code>[insert_weighted](crate::DictionaryBuilder::insert_weighted)</code>.
+    pub(crate) fn insert_bytes(&mut self, word: &[u8], weight: u64) -> Result<(), Error> {
+        if word.is_empty() {
+            return Ok(());
+        }
+
+        // A word is only added to the `Fst` once the next word is known to differ, so
+        // that the weights of repeated words can be added together first.
+        match self.pending {
+            Some((ref mut pending_word, ref mut pending_weight)) => match word.cmp(pending_word) {
+                Ordering::Equal => {
+                    merge_values(self.settings.value_kind(), word, pending_weight, weight)?
+                }
+                Ordering::Greater => {
+                    self.output.insert(pending_word, *pending_weight)?;
+                    pending_word.clear();
+                    pending_word.extend_from_slice(word);
+                    *pending_weight = weight;
+                }
+                Ordering::Less => {
+                    return Err(Error::WordOutOfOrder {
+                        previous: String::from_utf8_lossy(pending_word).into_owned(),
+                        got: String::from_utf8_lossy(word).into_owned(),
+                    })
+                }
+            },
+            None => self.pending = Some((word.to_vec(), weight)),
+        }
+
+        Ok(())
+    }
+
+    /// Adds <code>words</code> that are already canonicalized and sorted, as with
+    /// <code>[insert](crate::DictionaryBuilder::insert)</code>.
+    pub fn extend<I>(&mut self, words: I) -> Result<(), Error>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        for word in words {
+            self.insert(word)?;
+        }
+
+        Ok(())
+    }
+
+    /// Adds pairs of words and weights where the words are already canonicalized and
+    /// sorted, as with
+    /// <code>[insert_weighted](crate::DictionaryBuilder::insert_weighted)</code>.
+    pub fn extend_weighted<I, S>(&mut self, words: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = (S, u64)>,
+        S: AsRef<str>,
+    {
+        for (word, weight) in words {
+            self.insert_weighted(word, weight)?;
+        }
+
+        Ok(())
+    }
+
+    /// Adds <code>words</code> in any order and form.
+    ///
+    /// The words are canonicalized according to this builder's
+    /// <code>[Settings](crate::Settings)</code> and sorted in runs of at most
+    /// <code>[with_max_run_bytes](crate::DictionaryBuilder::with_max_run_bytes)</code>
+    /// bytes. If there is more than one run, each is spilled to a temporary file and the
+    /// runs are then merged, so memory use is bounded by the run size rather than the
+    /// number of words. Without the <code>std</code> feature, there is only ever one run.
+    ///
+    /// The words must all sort after any words that were previously added.
+    #[inline]
+    pub fn extend_unsorted<I>(&mut self, words: I) -> Result<(), Error>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        self.extend_unsorted_weighted(words.into_iter().map(|word| (word, 0)))
+    }
+
+    /// Adds pairs of words and weights in any order and form, as with
+    /// <code>[extend_unsorted](crate::DictionaryBuilder::extend_unsorted)</code>.
+    ///
+    /// The weights of words that are the same after canonicalization are combined as
+    /// with <code>[insert_weighted](crate::DictionaryBuilder::insert_weighted)</code>.
+    pub fn extend_unsorted_weighted<I, S>(&mut self, words: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = (S, u64)>,
+        S: AsRef<str>,
+    {
+        let mut run = Vec::new();
+        #[cfg(feature = "std")]
+        let mut run_bytes = 0_usize;
+        #[cfg(feature = "std")]
+        let mut spilled_runs = Vec::new();
+
+        for (word, weight) in words {
+            let word = word.as_ref();
+            if word.is_empty() || !self.accepts(word) {
+                continue;
+            }
+            self.check(word)?;
+
+            run.push((self.settings.canonicalize(word), weight));
+
+            #[cfg(feature = "std")]
+            {
+                run_bytes = run_bytes.saturating_add(run[run.len() - 1].0.len());
+                if run_bytes >= self.max_run_bytes {
+                    spilled_runs.push(self.spill(&mut run)?);
+                    run_bytes = 0;
+                }
+            }
+        }
+
+        #[cfg(feature = "std")]
+        if !spilled_runs.is_empty() {
+            if !run.is_empty() {
+                spilled_runs.push(self.spill(&mut run)?);
+            }
+
+            return self.merge(spilled_runs);
+        }
+
+        sort_run(&mut run, self.settings.value_kind())?;
+        run.iter()
+            .try_for_each(|(word, weight)| self.insert_bytes(word.as_bytes(), *weight))
+    }
+
+    /// Finishes building and returns the underlying
+    /// <code>[Write](std::io::Write)</code>r.
+    pub fn finish(mut self) -> Result<W, Error> {
+        if let Some((word, weight)) = self.pending.take() {
+            self.output.insert(&word, weight)?;
+        }
+
+        self.output.finish()
+    }
+
+    /// Sorts <code>run</code>, writes it to a new temporary file and empties it.
+    #[cfg(feature = "std")]
+    fn spill(&self, run: &mut Vec<(String, u64)>) -> Result<File, Error> {
+        sort_run(run, self.settings.value_kind())?;
+
+        let file = match self.spill_dir {
+            Some(ref spill_dir) => tempfile::tempfile_in(spill_dir)?,
+            None => tempfile::tempfile()?,
+        };
+        let mut writer = BufWriter::new(file);
+        for (word, weight) in run.drain(..) {
+            writer.write_all(&(word.len() as u64).to_le_bytes())?;
+            writer.write_all(word.as_bytes())?;
+            writer.write_all(&weight.to_le_bytes())?;
+        }
+
+        let mut file = writer
+            .into_inner()
+            .map_err(io::IntoInnerError::into_error)?;
+        file.seek(SeekFrom::Start(0))?;
+        Ok(file)
+    }
+
+    /// Merges sorted runs that were spilled to files into the underlying
+    /// <code>[Fst](fst::raw::Fst)</code> builder.
+    #[cfg(feature = "std")]
+    fn merge(&mut self, runs: Vec<File>) -> Result<(), Error> {
+        let mut readers = runs.into_iter().map(BufReader::new).collect::<Vec<_>>();
+        let mut heap = BinaryHeap::with_capacity(readers.len());
+        for (index, reader) in readers.iter_mut().enumerate() {
+            if let Some((word, weight)) = read_spilled_word(reader)? {
+                heap.push(Reverse((word, index, weight)));
+            }
+        }
+
+        while let Some(Reverse((word, index, weight))) = heap.pop() {
+            self.insert_bytes(word.as_bytes(), weight)?;
+            if let Some((word, weight)) = read_spilled_word(&mut readers[index])? {
+                heap.push(Reverse((word, index, weight)));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Combines the <code>value</code> of a repeated <code>word</code> into the value it
+/// was first added with, according to <code>value_kind</code>.
+#[inline]
+fn merge_values(
+    value_kind: ValueKind,
+    word: &[u8],
+    kept_value: &mut u64,
+    value: u64,
+) -> Result<(), Error> {
+    match value_kind {
+        ValueKind::Weights => *kept_value = kept_value.saturating_add(value),
+        ValueKind::Payloads if *kept_value != value => {
+            return Err(Error::ConflictingPayloads {
+                word: String::from_utf8_lossy(word).into_owned(),
+                first: *kept_value,
+                second: value,
+            })
+        }
+        ValueKind::Payloads => {}
+    }
+
+    Ok(())
+}
+
+/// Sorts <code>run</code> by bytes and merges repeated words, combining their values
+/// according to <code>value_kind</code>.
+///
+/// The sort is stable, so the first of a repeated word's values is the one it was
+/// first added with.
+fn sort_run(run: &mut Vec<(String, u64)>, value_kind: ValueKind) -> Result<(), Error> {
+    run.sort_by(|(word1, _), (word2, _)| word1.as_bytes().cmp(word2.as_bytes()));
+
+    let mut merged = Ok(());
+    run.dedup_by(|(word, value), (kept_word, kept_value)| {
+        let repeated = word == kept_word;
+        if repeated && merged.is_ok() {
+            merged = merge_values(value_kind, kept_word.as_bytes(), kept_value, *value);
+        }
+        repeated
+    });
+    merged
+}
+
+/// Reads the next word and weight of a spilled run, if there is one.
+#[cfg(feature = "std")]
+fn read_spilled_word<R>(reader: &mut R) -> io::Result<Option<(String, u64)>>
+where
+    R: Read,
+{
+    let mut len = [0; 8];
+    match reader.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+
+    let mut word = vec![0; u64::from_le_bytes(len) as usize];
+    reader.read_exact(&mut word)?;
+    let word =
+        String::from_utf8(word).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    let mut weight = [0; 8];
+    reader.read_exact(&mut weight)?;
+    Ok(Some((word, u64::from_le_bytes(weight))))
+}
+
+/// Where the underlying <code>[Fst](fst::raw::Fst)</code> builder of a
+/// <code>[DictionaryBuilder](crate::DictionaryBuilder)</code> writes.
+enum Output<W> {
+    /// Straight to the writer, fingerprinting the bytes as they pass through.
+    #[cfg(feature = "std")]
+    Streaming(fst::raw::Builder<Fingerprinting<W>>),
+    /// To memory, copying the bytes and their fingerprint to the writer once the
+    /// <code>[Fst](fst::raw::Fst)</code> is finished, as the underlying builder can only
+    /// stream to a <code>[Write](std::io::Write)</code>r with the <code>std</code> feature.
+    #[cfg(not(feature = "std"))]
+    Buffered(fst::raw::Builder<Vec<u8>>, W),
+}
+
+impl<W> Output<W>
+where
+    W: Write,
+{
+    #[inline]
+    fn insert(&mut self, word: &[u8], weight: u64) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "std")]
+            Output::Streaming(builder) => builder.insert(word, weight)?,
+            #[cfg(not(feature = "std"))]
+            Output::Buffered(builder, _) => builder.insert(word, weight)?,
+        }
+
+        Ok(())
+    }
+
+    fn finish(self) -> Result<W, Error> {
+        let (mut writer, fingerprint) = match self {
+            #[cfg(feature = "std")]
+            Output::Streaming(builder) => {
+                let Fingerprinting {
+                    writer,
+                    fingerprint,
+                } = builder.into_inner()?;
+                (writer, fingerprint)
+            }
+            #[cfg(not(feature = "std"))]
+            Output::Buffered(builder, mut writer) => {
+                let fst = builder.into_inner()?;
+                writer.write_all(&fst)?;
+                (writer, format::fingerprint(&fst))
+            }
+        };
+
+        writer.write_all(&fingerprint.to_le_bytes())?;
+        Ok(writer)
+    }
+}
+
+#[cfg(not(feature = "std"))]
+mod sink {
+    use alloc::vec::Vec;
+
+    use crate::Error;
+
+    /// What a <code>[DictionaryBuilder](crate::DictionaryBuilder)</code> can write to
+    /// without the <code>std</code> feature, which is only memory.
+    pub trait Write {
+        /// Writes all of <code>bytes</code>.
+        fn write_all(&mut self, bytes: &[u8]) -> Result<(), Error>;
+    }
+
+    impl Write for Vec<u8> {
+        #[inline]
+        fn write_all(&mut self, bytes: &[u8]) -> Result<(), Error> {
+            self.extend_from_slice(bytes);
+            Ok(())
+        }
+    }
+}
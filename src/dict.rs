@@ -1,21 +1,287 @@
 use alloc::string::String;
 use alloc::vec::Vec;
+use core::fmt;
 use fst::raw::Fst;
-use unicode_normalization::UnicodeNormalization;
+use fst::Streamer;
 
-use crate::WordSegmentations;
+use crate::format;
+use crate::{
+    DictionaryBuilder, PatternError, Settings, SettingsMismatch, ValueKind, WordFilter,
+    WordSegmentations,
+};
 
-pub use fst::raw::Error;
+/// The error returned when loading, building or rewriting a
+/// <code>[Dictionary](crate::Dictionary)</code> fails.
+///
+/// Methods that can only fail in one way, such as parsing a
+/// <code>[Pattern](crate::Pattern)</code> or combining two
+/// <code>[Dictionary](crate::Dictionary)</code>s, return a more precise error, which
+/// converts into this one so that <code>?</code> works across both.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The bytes don't start with the header of a serialized
+    /// <code>[Dictionary](crate::Dictionary)</code>. Bytes from before the header was
+    /// added can be rewritten with
+    /// <code>[Dictionary](crate::Dictionary)::[migrate](crate::Dictionary::migrate)</code>.
+    NotADictionary,
+    /// The bytes are in a format version that this library doesn't read. Bytes in an
+    /// older version can be rewritten with
+    /// <code>[Dictionary](crate::Dictionary)::[migrate](crate::Dictionary::migrate)</code>.
+    UnsupportedVersion {
+        /// The format version that this library reads.
+        expected: u16,
+        /// The format version of the bytes.
+        got: u16,
+    },
+    /// The header holds <code>[Settings](crate::Settings)</code> that this library
+    /// doesn't know.
+    InvalidSettings,
+    /// The checksum of the underlying <code>[Fst](fst::raw::Fst)</code> doesn't match
+    /// its bytes, so they were corrupted.
+    ChecksumMismatch {
+        /// The checksum stored in the bytes.
+        expected: u32,
+        /// The checksum of the bytes.
+        got: u32,
+    },
+    /// The fingerprint at the end of the bytes doesn't match the words.
+    FingerprintMismatch {
+        /// The fingerprint stored in the bytes.
+        expected: u64,
+        /// The fingerprint of the words.
+        got: u64,
+    },
+    /// The underlying <code>[Fst](fst::raw::Fst)</code> is corrupt.
+    Fst(fst::raw::Error),
+    /// A word given to a <code>[DictionaryBuilder](crate::DictionaryBuilder)</code> as
+    /// already sorted sorts before the word added before it.
+    WordOutOfOrder {
+        /// The word added before it.
+        previous: String,
+        /// The word that is out of order.
+        got: String,
+    },
+    /// A word starts or ends partway through an extended grapheme cluster, such as a
+    /// lone combining mark, so it combines with the text around it and could never be
+    /// found by
+    /// <code>[Dictionary](crate::Dictionary)::[word_segmentations](crate::Dictionary::word_segmentations)</code>.
+    InvalidWord {
+        /// The word as it was given.
+        word: String,
+    },
+    /// A key of an external <code>[fst::Set](fst::Set)</code> or
+    /// <code>[fst::Map](fst::Map)</code> is empty, isn't UTF-8 or isn't canonical
+    /// according to the <code>[Settings](crate::Settings)</code>, so it can't be a word.
+    InvalidKey {
+        /// The key.
+        key: Vec<u8>,
+    },
+    /// A word was given two different payloads while building a
+    /// <code>[Dictionary](crate::Dictionary)</code> whose
+    /// <code>[Settings](crate::Settings)</code> have a
+    /// <code>[ValueKind](crate::ValueKind)</code> of
+    /// <code>[Payloads](crate::ValueKind::Payloads)</code>.
+    ConflictingPayloads {
+        /// The canonicalized word.
+        word: String,
+        /// The payload that the word was given first.
+        first: u64,
+        /// The payload that the word was given later.
+        second: u64,
+    },
+    /// A line of a word list is not valid UTF-8.
+    InvalidUtf8 {
+        /// The line number, starting from 1.
+        line: usize,
+        /// Where in the line the UTF-8 is invalid.
+        error: core::str::Utf8Error,
+    },
+    /// A <code>[Pattern](crate::Pattern)</code> can't be parsed.
+    Pattern(PatternError),
+    /// Two <code>[Dictionary](crate::Dictionary)</code>s with different
+    /// <code>[Settings](crate::Settings)</code> were combined.
+    SettingsMismatch(SettingsMismatch),
+    /// Reading or writing the bytes failed.
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotADictionary => f.write_str("bytes are not a serialized dictionary"),
+            Error::UnsupportedVersion { expected, got } => write!(
+                f,
+                "dictionary format version {} is not supported (expected version {})",
+                got, expected
+            ),
+            Error::InvalidSettings => f.write_str("dictionary header has invalid settings"),
+            Error::ChecksumMismatch { expected, got } => write!(
+                f,
+                "dictionary checksum mismatch (expected {:#010x}, got {:#010x})",
+                expected, got
+            ),
+            Error::FingerprintMismatch { expected, got } => write!(
+                f,
+                "dictionary fingerprint mismatch (expected {:#018x}, got {:#018x})",
+                expected, got
+            ),
+            Error::Fst(e) => write!(f, "corrupt dictionary: {}", e),
+            Error::WordOutOfOrder { previous, got } => write!(
+                f,
+                "word {:?} was added out of order after {:?}",
+                got, previous
+            ),
+            Error::InvalidWord { word } => write!(
+                f,
+                "word {:?} starts or ends partway through a grapheme",
+                word
+            ),
+            Error::InvalidKey { key } => write!(
+                f,
+                "fst key {:?} is empty, not UTF-8 or not canonical",
+                String::from_utf8_lossy(key)
+            ),
+            Error::ConflictingPayloads {
+                word,
+                first,
+                second,
+            } => write!(
+                f,
+                "word {:?} was given the different payloads {} and {}",
+                word, first, second
+            ),
+            Error::InvalidUtf8 { line, error } => write!(
+                f,
+                "line {} of word list is not valid UTF-8: {}",
+                line, error
+            ),
+            Error::Pattern(e) => write!(f, "invalid pattern: {}", e),
+            Error::SettingsMismatch(e) => e.fmt(f),
+            #[cfg(feature = "std")]
+            Error::Io(e) => write!(f, "dictionary I/O failed: {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Fst(e) => Some(e),
+            Error::InvalidUtf8 { error, .. } => Some(error),
+            Error::Pattern(e) => Some(e),
+            Error::SettingsMismatch(e) => Some(e),
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<fst::raw::Error> for Error {
+    fn from(e: fst::raw::Error) -> Self {
+        match e {
+            fst::raw::Error::ChecksumMismatch { expected, got } => {
+                Error::ChecksumMismatch { expected, got }
+            }
+            fst::raw::Error::OutOfOrder { previous, got } => Error::WordOutOfOrder {
+                previous: String::from_utf8_lossy(&previous).into_owned(),
+                got: String::from_utf8_lossy(&got).into_owned(),
+            },
+            e => Error::Fst(e),
+        }
+    }
+}
+
+impl From<fst::Error> for Error {
+    #[inline]
+    fn from(e: fst::Error) -> Self {
+        match e {
+            fst::Error::Fst(e) => e.into(),
+            #[cfg(feature = "std")]
+            fst::Error::Io(e) => Error::Io(e),
+            // Without the `std` feature, this library only reads and writes bytes in
+            // memory, which can't fail.
+            #[cfg(not(feature = "std"))]
+            fst::Error::Io(_) => unreachable!("got an I/O error without the std feature"),
+        }
+    }
+}
+
+impl From<PatternError> for Error {
+    #[inline]
+    fn from(e: PatternError) -> Self {
+        Error::Pattern(e)
+    }
+}
+
+impl From<SettingsMismatch> for Error {
+    #[inline]
+    fn from(e: SettingsMismatch) -> Self {
+        Error::SettingsMismatch(e)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Error {
+    #[inline]
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+/// The message of the panic when building a
+/// <code>[Dictionary](crate::Dictionary)</code> in memory fails, which can't happen as
+/// the words are sorted before they are added, writing to a
+/// <code>[Vec](alloc::vec::Vec)</code> can't fail, and the values of repeated words are
+/// either weights, which are added together, or all the same.
+pub(crate) const IN_MEMORY_BUILD: &str = "building a dictionary in memory failed";
+
+/// Builds a <code>[Dictionary](crate::Dictionary)</code> in memory from pairs of words
+/// and values in any order, with <code>builder</code>.
+pub(crate) fn build_in_memory<I, S>(
+    builder: DictionaryBuilder<Vec<u8>>,
+    words: I,
+) -> Result<Dictionary<Vec<u8>>, Error>
+where
+    I: IntoIterator<Item = (S, u64)>,
+    S: AsRef<str>,
+{
+    // Sort in memory, as the words are about to be stored in memory anyway.
+    let mut builder = builder.with_max_run_bytes(usize::MAX);
+    builder.extend_unsorted_weighted(words)?;
+    builder.into_dictionary()
+}
 
 /// Stores a dictionary's words so that word segmentation is speedy. Canonicalizes the
-/// Unicode to NFD form.
+/// Unicode to the normalization form of its <code>[Settings](crate::Settings)</code>
+/// (NFD by default) and applies their case folding.
 ///
 /// <code>D</code> is the backing storage for the dictionary, which must implement
 /// <code>[AsRef](core::convert::AsRef)&lt;&#91;[u8](core::primitive::u8)&#93;&gt;</code>.
 #[derive(Clone)]
-#[repr(transparent)]
 pub struct Dictionary<D> {
-    pub(crate) fst: Fst<D>,
+    pub(crate) fst: Fst<Body<D>>,
+    pub(crate) settings: Settings,
+}
+
+/// The backing storage of a <code>[Dictionary](crate::Dictionary)</code> without the
+/// header at its start or the fingerprint at its end, which is what the underlying
+/// <code>[Fst](fst::raw::Fst)</code> reads.
+#[derive(Clone)]
+#[repr(transparent)]
+pub(crate) struct Body<D>(pub(crate) D);
+
+impl<D> AsRef<[u8]> for Body<D>
+where
+    D: AsRef<[u8]>,
+{
+    #[inline(always)]
+    fn as_ref(&self) -> &[u8] {
+        let bytes = self.0.as_ref();
+        &bytes[format::HEADER_LEN..bytes.len() - format::FOOTER_LEN]
+    }
 }
 
 impl Dictionary<Vec<u8>> {
@@ -24,7 +290,9 @@ impl Dictionary<Vec<u8>> {
     /// from its <code>words</code>.
     ///
     /// <b>Note:</b> capitalization is preserved, so the words "Arrow" and "box" will
-    /// not be a valid segmentation of "arrowbox".
+    /// not be a valid segmentation of "arrowbox". Use
+    /// <code>[Dictionary](crate::Dictionary)::[with_settings](crate::Dictionary::with_settings)</code>
+    /// to fold case instead.
     ///
     /// # Examples
     ///
@@ -48,6 +316,276 @@ impl Dictionary<Vec<u8>> {
     {
         words.iter().collect()
     }
+
+    /// Creates a new
+    /// <code>[Dictionary](crate::Dictionary)&lt;[Vec](alloc::vec::Vec)&lt;[u8](core::primitive::u8)&gt;&gt;</code>
+    /// from its <code>words</code>, as with
+    /// <code>[Dictionary](crate::Dictionary)::[new](crate::Dictionary::new)</code>, but
+    /// returning an <code>[Error](crate::Error)</code> for words that could never be found,
+    /// as with
+    /// <code>[try_with_settings](crate::Dictionary::try_with_settings)</code>.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::Dictionary;
+    ///
+    /// let dictionary = Dictionary::try_new(&["just", "ice", "justice"]).unwrap();
+    ///
+    /// assert_eq!(dictionary.word_segmentations("justice").count(), 2);
+    /// ```
+    #[inline]
+    pub fn try_new<S>(words: &[S]) -> Result<Self, Error>
+    where
+        S: AsRef<str>,
+    {
+        Self::try_from_iter(words)
+    }
+
+    /// Creates a new
+    /// <code>[Dictionary](crate::Dictionary)&lt;[Vec](alloc::vec::Vec)&lt;[u8](core::primitive::u8)&gt;&gt;</code>
+    /// from an <code>[Iterator](core::iter::Iterator)</code> over strings, as with
+    /// <code>[collect](core::iter::Iterator::collect)</code>, but returning an
+    /// <code>[Error](crate::Error)</code> for words that could never be found, as with
+    /// <code>[try_with_settings](crate::Dictionary::try_with_settings)</code>.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::Dictionary;
+    ///
+    /// let words = "just ice justice".split(' ');
+    /// let dictionary = Dictionary::try_from_iter(words).unwrap();
+    ///
+    /// assert!(dictionary.contains("justice"));
+    /// ```
+    #[inline]
+    pub fn try_from_iter<I>(words: I) -> Result<Self, Error>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        Self::try_with_settings(words, Settings::default())
+    }
+
+    /// Creates a new
+    /// <code>[Dictionary](crate::Dictionary)&lt;[Vec](alloc::vec::Vec)&lt;[u8](core::primitive::u8)&gt;&gt;</code>
+    /// from its <code>words</code>, canonicalizing them according to the given
+    /// <code>[Settings](crate::Settings)</code>.
+    ///
+    /// Every word has a value of zero, so words that are the same after
+    /// canonicalization never have conflicting payloads, whatever the
+    /// <code>[ValueKind](crate::ValueKind)</code> of <code>settings</code>.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::{CaseFolding, Dictionary, Settings};
+    ///
+    /// let settings = Settings::new().with_case_folding(CaseFolding::Full);
+    /// let dictionary = Dictionary::with_settings(["Arrow", "box"], settings);
+    ///
+    /// assert_eq!(
+    ///     dictionary.word_segmentations("arrowbox").collect::<Vec<_>>(),
+    ///     [vec!["arrow", "box"]]
+    /// );
+    /// ```
+    pub fn with_settings<I>(words: I, settings: Settings) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let words = words.into_iter().map(|word| (word, 0));
+        build_in_memory(DictionaryBuilder::memory(settings), words).expect(IN_MEMORY_BUILD)
+    }
+
+    /// Creates a new
+    /// <code>[Dictionary](crate::Dictionary)&lt;[Vec](alloc::vec::Vec)&lt;[u8](core::primitive::u8)&gt;&gt;</code>
+    /// from its <code>words</code>, as with
+    /// <code>[Dictionary](crate::Dictionary)::[with_settings](crate::Dictionary::with_settings)</code>,
+    /// but checking the words first.
+    ///
+    /// A word that starts or ends partway through an extended grapheme cluster, such as
+    /// a lone combining mark, combines with the text around it and could never be
+    /// found, so it is an
+    /// <code>[Error](crate::Error)::[InvalidWord](crate::Error::InvalidWord)</code>
+    /// instead of being kept.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::{Dictionary, Error, Settings};
+    ///
+    /// let result = Dictionary::try_with_settings(["just", "\u{301}"], Settings::new());
+    ///
+    /// assert!(matches!(result, Err(Error::InvalidWord { .. })));
+    /// ```
+    pub fn try_with_settings<I>(words: I, settings: Settings) -> Result<Self, Error>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let builder = DictionaryBuilder::memory(settings).rejecting_invalid_words();
+        build_in_memory(builder, words.into_iter().map(|word| (word, 0)))
+    }
+
+    /// Creates a new
+    /// <code>[Dictionary](crate::Dictionary)&lt;[Vec](alloc::vec::Vec)&lt;[u8](core::primitive::u8)&gt;&gt;</code>
+    /// from the <code>words</code> that <code>filter</code> accepts, as with
+    /// <code>[Dictionary](crate::Dictionary)::[with_settings](crate::Dictionary::with_settings)</code>.
+    ///
+    /// Words are checked as they were given, before they are canonicalized.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::{CharacterClasses, Dictionary, Settings, WordFilter};
+    ///
+    /// let filter = WordFilter::new().with_allowed_characters(CharacterClasses::ALPHABETIC);
+    /// let dictionary = Dictionary::with_filter(["just", "ice", "ice's", "r2d2"], Settings::new(), filter);
+    ///
+    /// assert_eq!(dictionary.words().collect::<Vec<_>>(), ["ice", "just"]);
+    /// ```
+    pub fn with_filter<I>(words: I, settings: Settings, filter: WordFilter) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let builder = DictionaryBuilder::memory(settings).with_filter(filter);
+        build_in_memory(builder, words.into_iter().map(|word| (word, 0))).expect(IN_MEMORY_BUILD)
+    }
+
+    /// Creates a new
+    /// <code>[Dictionary](crate::Dictionary)&lt;[Vec](alloc::vec::Vec)&lt;[u8](core::primitive::u8)&gt;&gt;</code>
+    /// from the <code>words</code> that <code>filter</code> accepts, as with
+    /// <code>[Dictionary](crate::Dictionary)::[with_filter](crate::Dictionary::with_filter)</code>,
+    /// but returning an <code>[Error](crate::Error)</code> for accepted words that could
+    /// never be found, as with
+    /// <code>[try_with_settings](crate::Dictionary::try_with_settings)</code>.
+    pub fn try_with_filter<I>(
+        words: I,
+        settings: Settings,
+        filter: WordFilter,
+    ) -> Result<Self, Error>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let builder = DictionaryBuilder::memory(settings)
+            .with_filter(filter)
+            .rejecting_invalid_words();
+        build_in_memory(builder, words.into_iter().map(|word| (word, 0)))
+    }
+
+    /// Creates a new
+    /// <code>[Dictionary](crate::Dictionary)&lt;[Vec](alloc::vec::Vec)&lt;[u8](core::primitive::u8)&gt;&gt;</code>
+    /// from pairs of words and their weights, such as their frequencies, canonicalizing
+    /// the words according to the given <code>[Settings](crate::Settings)</code>.
+    ///
+    /// The weights are stored in the underlying <code>[Fst](fst::raw::Fst)</code>, so they
+    /// survive <code>[as_bytes](crate::Dictionary::as_bytes)</code> and
+    /// <code>[from_bytes](crate::Dictionary::from_bytes)</code>, and can be read with
+    /// <code>[weight](crate::Dictionary::weight)</code>. The weights of words that are the
+    /// same after canonicalization are added together.
+    ///
+    /// The values are always weights, so the <code>[ValueKind](crate::ValueKind)</code>
+    /// of <code>settings</code> is replaced with
+    /// <code>[Weights](crate::ValueKind::Weights)</code>. Use
+    /// <code>[with_payloads](crate::Dictionary::with_payloads)</code> for payloads.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::{Dictionary, Settings};
+    ///
+    /// let dictionary = Dictionary::with_weights(
+    ///     [("just", 300), ("ice", 200), ("justice", 100)],
+    ///     Settings::new(),
+    /// );
+    /// let dictionary = Dictionary::from_bytes(dictionary.as_bytes()).unwrap();
+    ///
+    /// assert_eq!(dictionary.weight("justice"), Some(100));
+    /// ```
+    pub fn with_weights<I, S>(words: I, settings: Settings) -> Self
+    where
+        I: IntoIterator<Item = (S, u64)>,
+        S: AsRef<str>,
+    {
+        let settings = settings.with_value_kind(ValueKind::Weights);
+        build_in_memory(DictionaryBuilder::memory(settings), words).expect(IN_MEMORY_BUILD)
+    }
+
+    /// Creates a new
+    /// <code>[Dictionary](crate::Dictionary)&lt;[Vec](alloc::vec::Vec)&lt;[u8](core::primitive::u8)&gt;&gt;</code>
+    /// from pairs of words and their weights, as with
+    /// <code>[Dictionary](crate::Dictionary)::[with_weights](crate::Dictionary::with_weights)</code>,
+    /// but returning an <code>[Error](crate::Error)</code> for words that could never be
+    /// found, as with
+    /// <code>[try_with_settings](crate::Dictionary::try_with_settings)</code>.
+    pub fn try_with_weights<I, S>(words: I, settings: Settings) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = (S, u64)>,
+        S: AsRef<str>,
+    {
+        let settings = settings.with_value_kind(ValueKind::Weights);
+        let builder = DictionaryBuilder::memory(settings).rejecting_invalid_words();
+        build_in_memory(builder, words)
+    }
+}
+
+impl Dictionary<Vec<u8>> {
+    /// Rewrites the bytes of a <code>[Dictionary](crate::Dictionary)</code> from an
+    /// older version of this library in the current byte format.
+    ///
+    /// This accepts the bare <code>[Fst](fst::raw::Fst)</code> bytes of version 0.3,
+    /// whose words are in NFD with capitalization preserved, bytes in version 1 of the
+    /// byte format, whose header has no <code>[ValueKind](crate::ValueKind)</code> and
+    /// whose values are weights, and the bytes of the current format, which are
+    /// returned unchanged after being verified.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::Dictionary;
+    ///
+    /// let old_bytes = fst::Set::from_iter(["ice", "just", "justice"])
+    ///     .unwrap()
+    ///     .into_fst()
+    ///     .into_inner();
+    /// assert!(Dictionary::from_bytes(&old_bytes[..]).is_err());
+    ///
+    /// let dictionary = Dictionary::from_bytes(Dictionary::migrate(&old_bytes).unwrap()).unwrap();
+    /// assert_eq!(dictionary.word_segmentations("justice").count(), 2);
+    /// ```
+    pub fn migrate(bytes: &[u8]) -> Result<Vec<u8>, Error> {
+        if bytes.starts_with(&format::MAGIC) {
+            let bytes = match format::version(bytes) {
+                Some(1) => format::upgrade_v1(bytes)?,
+                _ => bytes.to_vec(),
+            };
+            return Dictionary::from_bytes_verified(bytes)
+                .map(|dictionary| dictionary.fst.into_inner().0);
+        }
+
+        let fst = match Fst::new(bytes) {
+            Ok(fst) => fst,
+            Err(_) => return Err(Error::NotADictionary),
+        };
+        match fst.verify() {
+            Ok(()) | Err(fst::Error::Fst(fst::raw::Error::ChecksumMissing)) => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        // Rebuild rather than copy the `Fst`, so that one from before checksums were
+        // added gets one.
+        let mut builder = DictionaryBuilder::memory(Settings::new());
+        let mut stream = fst.stream();
+        while let Some((word, output)) = stream.next() {
+            builder.insert_bytes(word, output.value())?;
+        }
+
+        builder.finish()
+    }
 }
 
 impl<D> Dictionary<D>
@@ -67,9 +605,10 @@ where
     /// This can be used in conjuction with loading the bytes from disk (perhaps by
     /// using <code>[include_bytes!](core::include_bytes)</code>).
     ///
-    /// <b>Note:</b> the byte format of the <code>[Dictionary](crate::Dictionary)</code>
-    /// may change on major updates of this library, requiring the bytes of a
-    /// <code>[Dictionary](crate::Dictionary)</code> to be regenerated in the new format.
+    /// The bytes start with a header holding a magic number, the format version and the
+    /// <code>[Settings](crate::Settings)</code>, and end with a fingerprint of the
+    /// words. Bytes from before the header was added can be rewritten with
+    /// <code>[Dictionary](crate::Dictionary)::[migrate](crate::Dictionary::migrate)</code>.
     ///
     /// # Examples
     ///
@@ -89,7 +628,115 @@ where
     /// ```
     #[inline(always)]
     pub fn as_bytes(&self) -> &[u8] {
-        self.fst.as_bytes()
+        self.fst.as_inner().0.as_ref()
+    }
+
+    /// Returns the fingerprint of the words of this
+    /// <code>[Dictionary](crate::Dictionary)</code>, which is stored at the end of its
+    /// bytes.
+    ///
+    /// Dictionaries with the same canonicalized words and weights have the same
+    /// fingerprint, so it can be used to tell whether serialized dictionaries differ
+    /// without comparing all of their bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::Dictionary;
+    ///
+    /// let first = Dictionary::new(&["just", "ice", "justice"]);
+    /// let second = Dictionary::new(&["justice", "ice", "just"]);
+    /// let third = Dictionary::new(&["just", "ice"]);
+    ///
+    /// assert_eq!(first.fingerprint(), second.fingerprint());
+    /// assert_ne!(first.fingerprint(), third.fingerprint());
+    /// ```
+    #[inline]
+    pub fn fingerprint(&self) -> u64 {
+        format::stored_fingerprint(self.as_bytes())
+    }
+
+    /// Returns the <code>[Settings](crate::Settings)</code> that this
+    /// <code>[Dictionary](crate::Dictionary)</code> was built with.
+    #[inline(always)]
+    pub fn settings(&self) -> Settings {
+        self.settings
+    }
+
+    /// Returns whether <code>word</code> is in this
+    /// <code>[Dictionary](crate::Dictionary)</code>, after canonicalizing it according to
+    /// its <code>[Settings](crate::Settings)</code>.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::Dictionary;
+    ///
+    /// let dictionary = Dictionary::new(&["café", "just", "ice"]);
+    ///
+    /// assert!(dictionary.contains("ice"));
+    /// assert!(dictionary.contains("cafe\u{301}"));
+    /// assert!(!dictionary.contains("justice"));
+    /// ```
+    #[inline]
+    pub fn contains(&self, word: &str) -> bool {
+        !word.is_empty() && self.fst.contains_key(self.settings.canonicalize(word))
+    }
+
+    /// Returns the weight of <code>word</code>, after canonicalizing it according to the
+    /// <code>[Settings](crate::Settings)</code> of this
+    /// <code>[Dictionary](crate::Dictionary)</code>, or
+    /// <code>[None](core::option::Option::None)</code> if it isn't in this
+    /// <code>[Dictionary](crate::Dictionary)</code>.
+    ///
+    /// Words weigh zero unless the <code>[Dictionary](crate::Dictionary)</code> was built
+    /// with weights.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::{Dictionary, Settings};
+    ///
+    /// let dictionary = Dictionary::with_weights(
+    ///     [("café", 3), ("cafe\u{301}", 4), ("just", 10)],
+    ///     Settings::new(),
+    /// );
+    ///
+    /// assert_eq!(dictionary.weight("café"), Some(7));
+    /// assert_eq!(dictionary.weight("just"), Some(10));
+    /// assert_eq!(dictionary.weight("ice"), None);
+    /// ```
+    #[inline]
+    pub fn weight(&self, word: &str) -> Option<u64> {
+        if word.is_empty() {
+            None
+        } else {
+            self.fst
+                .get(self.settings.canonicalize(word))
+                .map(|output| output.value())
+        }
+    }
+
+    /// Returns the number of words in this <code>[Dictionary](crate::Dictionary)</code>.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::Dictionary;
+    ///
+    /// let dictionary = Dictionary::new(&["café", "cafe\u{301}", "just", "ice", ""]);
+    ///
+    /// assert_eq!(dictionary.len(), 3);
+    /// ```
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.fst.len()
+    }
+
+    /// Returns whether this <code>[Dictionary](crate::Dictionary)</code> has no words.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.fst.is_empty()
     }
 
     /// Creates a new <code>[Dictionary](crate::Dictionary)</code> from the underlying
@@ -106,9 +753,10 @@ where
     /// This can be used in conjuction with loading the bytes from disk (perhaps by
     /// using <code>[include_bytes!](core::include_bytes)</code>).
     ///
-    /// <b>Note:</b> the byte format of the <code>[Dictionary](crate::Dictionary)</code>
-    /// may change on major updates of this library, requiring the bytes of a
-    /// <code>[Dictionary](crate::Dictionary)</code> to be regenerated in the new format.
+    /// The header of the bytes is checked, so bytes in a format version this library
+    /// doesn't read are rejected with
+    /// <code>[Error](crate::Error)::[UnsupportedVersion](crate::Error::UnsupportedVersion)</code>
+    /// instead of producing wrong segmentations.
     ///
     /// # Examples
     ///
@@ -127,11 +775,12 @@ where
     /// assert_eq!(word_segmentations, [vec!["just", "ice"], vec!["justice"]]);
     /// ```
     pub fn from_bytes(bytes: D) -> Result<Dictionary<D>, Error> {
-        match Fst::new(bytes) {
-            Ok(fst) => Ok(Dictionary { fst }),
-            Err(fst::Error::Fst(e)) => Err(e),
-            Err(_) => unreachable!("When loading a `Dictionary` from bytes, got an error unrelated to underlying `Fst`"),
-        }
+        let settings = format::decode_header(bytes.as_ref())?;
+
+        Ok(Dictionary {
+            fst: Fst::new(Body(bytes))?,
+            settings,
+        })
     }
 
     /// Creates a new <code>[Dictionary](crate::Dictionary)</code> from the underlying
@@ -148,9 +797,9 @@ where
     /// This can be used in conjuction with loading the bytes from disk (perhaps by
     /// using <code>[include_bytes!](core::include_bytes)</code>).
     ///
-    /// <b>Note:</b> the byte format of the <code>[Dictionary](crate::Dictionary)</code>
-    /// may change on major updates of this library, requiring the bytes of a
-    /// <code>[Dictionary](crate::Dictionary)</code> to be regenerated in the new format.
+    /// Along with the header and the checksum of the underlying
+    /// <code>[Fst](fst::raw::Fst)</code>, the fingerprint at the end of the bytes is
+    /// checked against the words.
     ///
     /// # Examples
     ///
@@ -169,21 +818,31 @@ where
     /// assert_eq!(word_segmentations, [vec!["just", "ice"], vec!["justice"]]);
     /// ```
     pub fn from_bytes_verified(bytes: D) -> Result<Dictionary<D>, Error> {
-        match Fst::new(bytes).and_then(|fst| {
-            fst.verify()?;
-            Ok(fst)
-        }) {
-            Ok(fst) => Ok(Dictionary { fst }),
-            Err(fst::Error::Fst(e)) => Err(e),
-            Err(_) => unreachable!("When loading a `Dictionary` from bytes, got an error unrelated to underlying `Fst`"),
+        let settings = format::decode_header(bytes.as_ref())?;
+
+        let fst = Fst::new(Body(bytes))?;
+        fst.verify()?;
+        let dictionary = Dictionary { fst, settings };
+
+        let expected = dictionary.fingerprint();
+        let got = format::fingerprint(dictionary.fst.as_bytes());
+        if expected != got {
+            return Err(Error::FingerprintMismatch { expected, got });
         }
+
+        Ok(dictionary)
     }
 
     /// Finds all segmentations into <code>[Dictionary](crate::Dictionary)</code> words
     /// of the given <code>input</code> string.
     ///
-    /// <b>Note:</b> capitalization is preserved, so the words "Arrow" and "box" will
-    /// not be a valid segmentation of "arrowbox".
+    /// The <code>input</code> is canonicalized with the same
+    /// <code>[Settings](crate::Settings)</code> as the words, but the segmentations are
+    /// slices of the original <code>input</code>.
+    ///
+    /// <b>Note:</b> unless the <code>[Dictionary](crate::Dictionary)</code> was built
+    /// with <code>[CaseFolding](crate::CaseFolding)</code>, capitalization is preserved,
+    /// so the words "Arrow" and "box" will not be a valid segmentation of "arrowbox".
     ///
     /// # Examples
     ///
@@ -213,7 +872,9 @@ where
     /// from an <code>[Iterator](core::iter::Iterator)</code> over strings.
     ///
     /// <b>Note:</b> capitalization is preserved, so the words "Arrow" and "box" will
-    /// not be a valid segmentation of "arrowbox".
+    /// not be a valid segmentation of "arrowbox". Use
+    /// <code>[Dictionary](crate::Dictionary)::[with_settings](crate::Dictionary::with_settings)</code>
+    /// to fold case instead.
     ///
     /// # Examples
     ///
@@ -230,26 +891,62 @@ where
     /// word_segmentations.sort_unstable();
     /// assert_eq!(word_segmentations, [vec!["just", "ice"], vec!["justice"]]);
     /// ```
+    #[inline]
     fn from_iter<I>(words: I) -> Self
     where
         I: IntoIterator<Item = S>,
     {
-        let mut words = words
-            .into_iter()
-            .filter_map(|word| {
-                let word = word.as_ref();
-                if word.is_empty() {
-                    None
-                } else {
-                    Some(word.chars().nfd().collect::<String>())
-                }
-            })
-            .collect::<Vec<_>>();
-        words.sort_unstable_by(|word1, word2| word1.as_bytes().cmp(word2.as_bytes()));
-        words.dedup();
-
-        Dictionary {
-            fst: Fst::from_iter_set(words.into_iter()).unwrap(),
-        }
+        Dictionary::with_settings(words, Settings::default())
     }
 }
+
+/// Embeds a <code>[Dictionary](crate::Dictionary)</code> that a build script compiled
+/// from a word list with <code>[compile_word_list](crate::compile_word_list)</code>,
+/// evaluating to a
+/// <code>[Dictionary](crate::Dictionary)&lt;&amp;'static &#91;[u8](core::primitive::u8)&#93;&gt;</code>.
+///
+/// The argument is the same path that was given to
+/// <code>[compile_word_list](crate::compile_word_list)</code>. The bytes are embedded
+/// with <code>[include_bytes!](core::include_bytes)</code> and only their header is
+/// checked at run time, so there is no construction or verification cost.
+///
+/// # Panics
+///
+/// Panics if the embedded bytes don't have a valid header, which can only happen if
+/// they weren't written by <code>[compile_word_list](crate::compile_word_list)</code>
+/// from the same version of this library.
+///
+/// # Examples
+///
+/// After compiling <code>words.txt</code> in <code>build.rs</code>:
+///
+/// ```rust,ignore
+/// use wordbreaker::{include_dictionary, Dictionary};
+///
+/// let dictionary: Dictionary<&'static [u8]> = include_dictionary!("words.txt");
+/// ```
+#[macro_export]
+macro_rules! include_dictionary {
+    ($word_list:literal) => {
+        $crate::__include_dictionary_in!(env!("OUT_DIR"), $word_list)
+    };
+}
+
+/// Embeds the dictionary compiled from <code>$word_list</code> into
+/// <code>$out_dir</code>, as with
+/// <code>[include_dictionary!](crate::include_dictionary)</code>, which lets the tests
+/// embed a dictionary without a build script.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __include_dictionary_in {
+    ($out_dir:expr, $word_list:literal) => {
+        $crate::Dictionary::<&'static [u8]>::from_bytes(include_bytes!(concat!(
+            $out_dir, "/", $word_list, ".dict"
+        )))
+        .expect(concat!(
+            "the embedded dictionary ",
+            $word_list,
+            " is invalid"
+        ))
+    };
+}
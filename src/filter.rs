@@ -0,0 +1,271 @@
+use alloc::sync::Arc;
+use core::fmt;
+use core::ops::{BitOr, BitOrAssign};
+use unicode_normalization::char::is_combining_mark;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// A predicate that a <code>[WordFilter](crate::WordFilter)</code> checks words with.
+type Predicate = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// A set of classes of characters that a <code>[WordFilter](crate::WordFilter)</code>
+/// can allow, combined with <code>|</code>.
+///
+/// # Examples
+///
+/// ```rust
+/// use wordbreaker::CharacterClasses;
+///
+/// let classes = CharacterClasses::ALPHABETIC | CharacterClasses::MARKS;
+///
+/// assert!(classes.contains('é'));
+/// assert!(classes.contains('\u{301}'));
+/// assert!(!classes.contains('\''));
+/// assert!(!classes.contains('7'));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct CharacterClasses(u8);
+
+impl CharacterClasses {
+    /// No characters.
+    pub const NONE: Self = Self(0);
+    /// Alphabetic characters, as with
+    /// <code>[char::is_alphabetic](core::primitive::char::is_alphabetic)</code>.
+    pub const ALPHABETIC: Self = Self(1);
+    /// Combining marks, such as accents that were decomposed from the letters they
+    /// modify.
+    pub const MARKS: Self = Self(1 << 1);
+    /// Numeric characters, as with
+    /// <code>[char::is_numeric](core::primitive::char::is_numeric)</code>.
+    pub const NUMERIC: Self = Self(1 << 2);
+    /// Apostrophes, both <code>'</code> and <code>’</code>.
+    pub const APOSTROPHES: Self = Self(1 << 3);
+    /// Hyphens, both <code>-</code> and <code>‐</code>.
+    pub const HYPHENS: Self = Self(1 << 4);
+    /// Every character that is in none of the other classes.
+    pub const OTHER: Self = Self(1 << 5);
+    /// Every character.
+    pub const ALL: Self = Self((1 << 6) - 1);
+
+    /// Returns the class of <code>ch</code>.
+    fn of(ch: char) -> Self {
+        match ch {
+            '\'' | '\u{2019}' => Self::APOSTROPHES,
+            '-' | '\u{2010}' => Self::HYPHENS,
+            ch if ch.is_alphabetic() => Self::ALPHABETIC,
+            ch if is_combining_mark(ch) => Self::MARKS,
+            ch if ch.is_numeric() => Self::NUMERIC,
+            _ => Self::OTHER,
+        }
+    }
+
+    /// Returns whether <code>ch</code> is in one of these classes.
+    #[inline]
+    pub fn contains(self, ch: char) -> bool {
+        self.0 & Self::of(ch).0 != 0
+    }
+}
+
+impl Default for CharacterClasses {
+    /// Returns <code>[ALL](crate::CharacterClasses::ALL)</code>.
+    #[inline(always)]
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+impl BitOr for CharacterClasses {
+    type Output = Self;
+
+    #[inline(always)]
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for CharacterClasses {
+    #[inline(always)]
+    fn bitor_assign(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+}
+
+/// Which words a <code>[WordFilter](crate::WordFilter)</code> keeps, based on their
+/// capitalization.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum CaseFilter {
+    /// Every word is kept.
+    KeepAll,
+    /// Words with at least two letters that are all uppercase, such as acronyms, are
+    /// rejected.
+    RejectAllUppercase,
+    /// Words with any uppercase letter, such as proper nouns and acronyms, are rejected.
+    RejectUppercase,
+    /// Words that are case variants of a word that was already kept, such as "polish"
+    /// after "Polish", are rejected, so only the first spelling of each word is kept.
+    ///
+    /// Words are compared with full case folding, and repeats of the very same word are
+    /// still merged. Only a <code>[DictionaryBuilder](crate::DictionaryBuilder)</code>
+    /// knows which words it already kept, so
+    /// <code>[WordFilter::accepts](crate::WordFilter::accepts)</code> rejects no word
+    /// with this rule.
+    RejectCaseVariants,
+}
+
+impl CaseFilter {
+    /// Returns whether <code>word</code> is kept.
+    fn accepts(self, word: &str) -> bool {
+        match self {
+            Self::KeepAll => true,
+            Self::RejectAllUppercase => {
+                let mut letters = word.chars().filter(|ch| ch.is_alphabetic());
+                letters.clone().nth(1).is_none() || !letters.all(char::is_uppercase)
+            }
+            Self::RejectUppercase => !word.chars().any(char::is_uppercase),
+            Self::RejectCaseVariants => true,
+        }
+    }
+}
+
+impl Default for CaseFilter {
+    /// Returns <code>[KeepAll](crate::CaseFilter::KeepAll)</code>.
+    #[inline(always)]
+    fn default() -> Self {
+        Self::KeepAll
+    }
+}
+
+/// Rules for which words a <code>[DictionaryBuilder](crate::DictionaryBuilder)</code>
+/// keeps, so that raw word lists don't have to be cleaned up beforehand.
+///
+/// Words are checked as they were given, before they are canonicalized, so that
+/// capitalization can be checked even when
+/// <code>[Settings](crate::Settings)</code> fold case. A word is kept only if it
+/// passes every rule. By default, every word is kept.
+///
+/// # Examples
+///
+/// ```rust
+/// use wordbreaker::{CaseFilter, CharacterClasses, Dictionary, Settings, WordFilter};
+///
+/// let filter = WordFilter::new()
+///     .with_min_graphemes(2)
+///     .with_allowed_characters(CharacterClasses::ALPHABETIC | CharacterClasses::MARKS)
+///     .with_case_filter(CaseFilter::RejectAllUppercase)
+///     .with_predicate(|word| !word.ends_with("ing"));
+/// let dictionary = Dictionary::with_filter(
+///     ["a", "aardvark", "aardvark's", "NASA", "Paris", "mp3", "running"],
+///     Settings::new(),
+///     filter,
+/// );
+///
+/// assert_eq!(dictionary.words().collect::<Vec<_>>(), ["Paris", "aardvark"]);
+/// ```
+#[derive(Clone, Default)]
+pub struct WordFilter {
+    min_graphemes: usize,
+    max_graphemes: Option<usize>,
+    allowed_characters: CharacterClasses,
+    case_filter: CaseFilter,
+    predicate: Option<Predicate>,
+}
+
+impl WordFilter {
+    /// Creates a new <code>[WordFilter](crate::WordFilter)</code> that keeps every word.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rejects words with fewer than <code>min_graphemes</code> extended grapheme
+    /// clusters.
+    #[inline]
+    pub fn with_min_graphemes(mut self, min_graphemes: usize) -> Self {
+        self.min_graphemes = min_graphemes;
+        self
+    }
+
+    /// Rejects words with more than <code>max_graphemes</code> extended grapheme
+    /// clusters.
+    #[inline]
+    pub fn with_max_graphemes(mut self, max_graphemes: usize) -> Self {
+        self.max_graphemes = Some(max_graphemes);
+        self
+    }
+
+    /// Rejects words with any character that isn't in <code>allowed_characters</code>.
+    #[inline]
+    pub fn with_allowed_characters(mut self, allowed_characters: CharacterClasses) -> Self {
+        self.allowed_characters = allowed_characters;
+        self
+    }
+
+    /// Rejects words according to their capitalization.
+    #[inline]
+    pub fn with_case_filter(mut self, case_filter: CaseFilter) -> Self {
+        self.case_filter = case_filter;
+        self
+    }
+
+    /// Returns the rule for which words are kept based on their capitalization.
+    #[inline(always)]
+    pub(crate) fn case_filter(&self) -> CaseFilter {
+        self.case_filter
+    }
+
+    /// Rejects words for which <code>predicate</code> returns <code>false</code>.
+    ///
+    /// The predicate runs after every other rule except
+    /// <code>[RejectCaseVariants](crate::CaseFilter::RejectCaseVariants)</code>, so it
+    /// only sees words that passed them.
+    #[inline]
+    pub fn with_predicate<P>(mut self, predicate: P) -> Self
+    where
+        P: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        self.predicate = Some(Arc::new(predicate));
+        self
+    }
+
+    /// Returns whether <code>word</code> passes every rule of this
+    /// <code>[WordFilter](crate::WordFilter)</code>.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::WordFilter;
+    ///
+    /// let filter = WordFilter::new().with_max_graphemes(4);
+    ///
+    /// assert!(filter.accepts("cafe\u{301}"));
+    /// assert!(!filter.accepts("cafes"));
+    /// ```
+    pub fn accepts(&self, word: &str) -> bool {
+        if self.min_graphemes > 0 || self.max_graphemes.is_some() {
+            let graphemes = word.graphemes(true).count();
+            if graphemes < self.min_graphemes
+                || self.max_graphemes.is_some_and(|max| graphemes > max)
+            {
+                return false;
+            }
+        }
+
+        word.chars().all(|ch| self.allowed_characters.contains(ch))
+            && self.case_filter.accepts(word)
+            && self
+                .predicate
+                .as_ref()
+                .map_or(true, |predicate| predicate(word))
+    }
+}
+
+impl fmt::Debug for WordFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WordFilter")
+            .field("min_graphemes", &self.min_graphemes)
+            .field("max_graphemes", &self.max_graphemes)
+            .field("allowed_characters", &self.allowed_characters)
+            .field("case_filter", &self.case_filter)
+            .field("predicate", &self.predicate.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
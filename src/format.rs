@@ -0,0 +1,162 @@
+//! The byte format of a serialized <code>[Dictionary](crate::Dictionary)</code>:
+//!
+//! | Bytes        | Contents                                                        |
+//! |--------------|-----------------------------------------------------------------|
+//! | 8            | The magic number <code>b"WBRKDICT"</code>                       |
+//! | 2            | The format version, little endian                               |
+//! | 3            | The encoded <code>[Settings](crate::Settings)</code>            |
+//! | any          | The underlying <code>[Fst](fst::raw::Fst)</code>                |
+//! | 8            | The fingerprint of the <code>[Fst](fst::raw::Fst)</code> bytes  |
+//!
+//! The fingerprint comes last because a
+//! <code>[DictionaryBuilder](crate::DictionaryBuilder)</code> streams the words out
+//! before it knows it.
+//!
+//! Version 1 of the format had only two bytes of
+//! <code>[Settings](crate::Settings)</code>, without the
+//! <code>[ValueKind](crate::ValueKind)</code>, and always stored weights.
+
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::io::{self, Write};
+
+use crate::{Error, Settings};
+
+/// The bytes that every serialized <code>[Dictionary](crate::Dictionary)</code> starts
+/// with.
+pub(crate) const MAGIC: [u8; 8] = *b"WBRKDICT";
+
+/// The version of the byte format that this library reads and writes.
+pub(crate) const VERSION: u16 = 2;
+
+/// The number of bytes before the underlying <code>[Fst](fst::raw::Fst)</code>.
+pub(crate) const HEADER_LEN: usize = MAGIC.len() + 2 + Settings::ENCODED_LEN;
+
+/// The number of bytes before the underlying <code>[Fst](fst::raw::Fst)</code> in
+/// version 1 of the byte format.
+const V1_HEADER_LEN: usize = MAGIC.len() + 2 + 2;
+
+/// The number of bytes after the underlying <code>[Fst](fst::raw::Fst)</code>.
+pub(crate) const FOOTER_LEN: usize = 8;
+
+/// Returns the header of a serialized <code>[Dictionary](crate::Dictionary)</code>
+/// with the given <code>[Settings](crate::Settings)</code>.
+pub(crate) fn encode_header(settings: Settings) -> [u8; HEADER_LEN] {
+    let mut header = [0; HEADER_LEN];
+    header[..MAGIC.len()].copy_from_slice(&MAGIC);
+    header[MAGIC.len()..MAGIC.len() + 2].copy_from_slice(&VERSION.to_le_bytes());
+    header[MAGIC.len() + 2..].copy_from_slice(&settings.encode());
+    header
+}
+
+/// Checks the header of a serialized <code>[Dictionary](crate::Dictionary)</code> and
+/// returns its <code>[Settings](crate::Settings)</code>.
+pub(crate) fn decode_header(bytes: &[u8]) -> Result<Settings, Error> {
+    if bytes.len() < HEADER_LEN + FOOTER_LEN || bytes[..MAGIC.len()] != MAGIC {
+        return Err(Error::NotADictionary);
+    }
+
+    let version = u16::from_le_bytes([bytes[MAGIC.len()], bytes[MAGIC.len() + 1]]);
+    if version != VERSION {
+        return Err(Error::UnsupportedVersion {
+            expected: VERSION,
+            got: version,
+        });
+    }
+
+    Settings::decode(&bytes[MAGIC.len() + 2..HEADER_LEN]).ok_or(Error::InvalidSettings)
+}
+
+/// Returns the format version of bytes that start with the magic number.
+#[inline]
+pub(crate) fn version(bytes: &[u8]) -> Option<u16> {
+    match bytes.get(MAGIC.len()..MAGIC.len() + 2)? {
+        &[low, high] => Some(u16::from_le_bytes([low, high])),
+        _ => None,
+    }
+}
+
+/// Rewrites bytes in version 1 of the byte format in the current version.
+pub(crate) fn upgrade_v1(bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    if bytes.len() < V1_HEADER_LEN + FOOTER_LEN || bytes[..MAGIC.len()] != MAGIC {
+        return Err(Error::NotADictionary);
+    }
+
+    // The bytes of the settings that version 1 had are unchanged, and its values were
+    // always weights, as in `Settings::new`.
+    let mut encoded_settings = Settings::new().encode();
+    encoded_settings[..2].copy_from_slice(&bytes[MAGIC.len() + 2..V1_HEADER_LEN]);
+    let settings = Settings::decode(&encoded_settings).ok_or(Error::InvalidSettings)?;
+
+    // The fingerprint only covers the `Fst` bytes, so it stays valid.
+    let mut upgraded = Vec::with_capacity(bytes.len() - V1_HEADER_LEN + HEADER_LEN);
+    upgraded.extend_from_slice(&encode_header(settings));
+    upgraded.extend_from_slice(&bytes[V1_HEADER_LEN..]);
+    Ok(upgraded)
+}
+
+/// Returns the fingerprint stored at the end of a serialized
+/// <code>[Dictionary](crate::Dictionary)</code> whose header has been checked.
+#[inline]
+pub(crate) fn stored_fingerprint(bytes: &[u8]) -> u64 {
+    let mut fingerprint = [0; FOOTER_LEN];
+    fingerprint.copy_from_slice(&bytes[bytes.len() - FOOTER_LEN..]);
+    u64::from_le_bytes(fingerprint)
+}
+
+/// The 64-bit FNV-1a offset basis.
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// The 64-bit FNV-1a prime.
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// Continues the 64-bit FNV-1a hash <code>hash</code> over <code>bytes</code>.
+#[inline]
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// Returns the fingerprint of the bytes of an <code>[Fst](fst::raw::Fst)</code>.
+#[inline]
+pub(crate) fn fingerprint(fst: &[u8]) -> u64 {
+    fnv1a(FNV_OFFSET_BASIS, fst)
+}
+
+/// A <code>[Write](std::io::Write)</code>r that computes the fingerprint of the bytes
+/// written through it.
+#[cfg(feature = "std")]
+pub(crate) struct Fingerprinting<W> {
+    pub(crate) writer: W,
+    pub(crate) fingerprint: u64,
+}
+
+#[cfg(feature = "std")]
+impl<W> Fingerprinting<W> {
+    #[inline]
+    pub(crate) fn new(writer: W) -> Self {
+        Self {
+            writer,
+            fingerprint: FNV_OFFSET_BASIS,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W> Write for Fingerprinting<W>
+where
+    W: Write,
+{
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.writer.write(buf)?;
+        self.fingerprint = fnv1a(self.fingerprint, &buf[..written]);
+        Ok(written)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
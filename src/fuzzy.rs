@@ -0,0 +1,193 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use fst::raw::StreamWithState;
+use fst::{Automaton, IntoStreamer, Streamer};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::Dictionary;
+
+/// The <code>[Iterator](core::iter::Iterator)</code> over words and their edit distances
+/// in byte order that
+/// <code>[Dictionary](crate::Dictionary)::[fuzzy](crate::Dictionary::fuzzy)</code>
+/// produces.
+pub struct FuzzyMatches<'d> {
+    levenshtein: Levenshtein,
+    stream: StreamWithState<'d, Levenshtein>,
+}
+
+impl<'d> Iterator for FuzzyMatches<'d> {
+    type Item = (String, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let levenshtein = &self.levenshtein;
+        let (word, _, state) = self.stream.next()?;
+        // Only live states match, so the distance is always known.
+        let distance = state
+            .as_ref()
+            .map_or(usize::MAX, |state| levenshtein.distance(state));
+
+        Some((String::from_utf8_lossy(word).into_owned(), distance))
+    }
+}
+
+impl<'d> core::iter::FusedIterator for FuzzyMatches<'d> {}
+
+/// An <code>[Automaton](fst::Automaton)</code> that matches the words at most
+/// <code>max_edits</code> edits away from <code>graphemes</code>, counting in graphemes.
+#[derive(Clone)]
+struct Levenshtein {
+    /// The canonicalized graphemes of the word being looked up.
+    graphemes: Vec<String>,
+    max_edits: usize,
+}
+
+/// The state of a <code>[Levenshtein](Levenshtein)</code> automaton, or
+/// <code>[None](core::option::Option::None)</code> once no word can be close enough.
+type State = Option<LevenshteinState>;
+
+#[derive(Clone)]
+struct LevenshteinState {
+    /// The row of the edit distance table for the complete graphemes read so far.
+    row: Vec<usize>,
+    /// The bytes of the last grapheme read so far, which the next bytes could still
+    /// extend.
+    pending: Vec<u8>,
+}
+
+impl Levenshtein {
+    /// Returns the row of the edit distance table after <code>grapheme</code>.
+    fn next_row(&self, row: &[usize], grapheme: &[u8]) -> Vec<usize> {
+        let mut next_row = Vec::with_capacity(row.len());
+        next_row.push(row[0].saturating_add(1));
+        for (i, expected) in self.graphemes.iter().enumerate() {
+            let substitution = row[i].saturating_add(usize::from(expected.as_bytes() != grapheme));
+            let insertion = row[i + 1].saturating_add(1);
+            let deletion = next_row[i].saturating_add(1);
+            next_row.push(substitution.min(insertion).min(deletion));
+        }
+
+        next_row
+    }
+
+    /// Returns the edit distance of a whole word that ends at <code>state</code>.
+    fn distance(&self, state: &LevenshteinState) -> usize {
+        let row = if state.pending.is_empty() {
+            None
+        } else {
+            Some(self.next_row(&state.row, &state.pending))
+        };
+        let row = row.as_deref().unwrap_or(&state.row);
+
+        row[row.len() - 1]
+    }
+}
+
+impl Automaton for Levenshtein {
+    type State = State;
+
+    #[inline]
+    fn start(&self) -> Self::State {
+        Some(LevenshteinState {
+            row: (0..=self.graphemes.len()).collect(),
+            pending: Vec::new(),
+        })
+    }
+
+    #[inline]
+    fn is_match(&self, state: &Self::State) -> bool {
+        state
+            .as_ref()
+            .is_some_and(|state| self.distance(state) <= self.max_edits)
+    }
+
+    #[inline]
+    fn can_match(&self, state: &Self::State) -> bool {
+        state.is_some()
+    }
+
+    fn accept(&self, state: &Self::State, byte: u8) -> Self::State {
+        let state = state.as_ref()?;
+        let mut pending = Vec::with_capacity(state.pending.len() + 1);
+        pending.extend_from_slice(&state.pending);
+        pending.push(byte);
+
+        // Until the bytes are valid UTF-8, the last character is still incomplete.
+        let split = core::str::from_utf8(&pending)
+            .ok()
+            .and_then(|pending| pending.grapheme_indices(true).nth(1))
+            .map(|(split, _)| split);
+        let Some(split) = split else {
+            return Some(LevenshteinState {
+                row: state.row.clone(),
+                pending,
+            });
+        };
+
+        let row = self.next_row(&state.row, &pending[..split]);
+        if row.iter().all(|&edits| edits > self.max_edits) {
+            return None;
+        }
+
+        Some(LevenshteinState {
+            row,
+            pending: pending[split..].to_vec(),
+        })
+    }
+}
+
+impl<D> Dictionary<D>
+where
+    D: AsRef<[u8]>,
+{
+    /// Returns an <code>[Iterator](core::iter::Iterator)</code> over the words in this
+    /// <code>[Dictionary](crate::Dictionary)</code> that are at most
+    /// <code>max_edits</code> edits away from <code>word</code>, sorted by their bytes,
+    /// each with its distance.
+    ///
+    /// The distance is the Levenshtein distance between the canonicalized words
+    /// measured in extended grapheme clusters, so inserting, deleting or substituting an
+    /// accented letter is one edit whether or not it is stored decomposed. Only the
+    /// parts of the underlying <code>[Fst](fst::raw::Fst)</code> that could lead to a
+    /// close enough word are visited.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::Dictionary;
+    ///
+    /// let dictionary = Dictionary::new(&["cafe", "café", "cafés", "chafe", "justice"]);
+    ///
+    /// assert_eq!(
+    ///     dictionary.fuzzy("cafè", 1).collect::<Vec<_>>(),
+    ///     [("cafe".to_string(), 1), ("cafe\u{301}".to_string(), 1)]
+    /// );
+    /// assert_eq!(
+    ///     dictionary.fuzzy("cafe", 2).collect::<Vec<_>>(),
+    ///     [
+    ///         ("cafe".to_string(), 0),
+    ///         ("cafe\u{301}".to_string(), 1),
+    ///         ("cafe\u{301}s".to_string(), 2),
+    ///         ("chafe".to_string(), 1),
+    ///     ]
+    /// );
+    /// ```
+    pub fn fuzzy(&self, word: &str, max_edits: usize) -> FuzzyMatches<'_> {
+        let mut graphemes = Vec::new();
+        for grapheme in word.graphemes(true) {
+            graphemes.push(self.settings.canonicalize(grapheme));
+        }
+
+        let levenshtein = Levenshtein {
+            graphemes,
+            max_edits,
+        };
+
+        FuzzyMatches {
+            stream: self
+                .fst
+                .search_with_state(levenshtein.clone())
+                .into_stream(),
+            levenshtein,
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use fst::raw::Fst;
+use fst::Streamer;
+
+use crate::format::{self, FOOTER_LEN, HEADER_LEN};
+use crate::{Dictionary, Error, Settings};
+
+impl Dictionary<Vec<u8>> {
+    /// Creates a new
+    /// <code>[Dictionary](crate::Dictionary)&lt;[Vec](alloc::vec::Vec)&lt;[u8](core::primitive::u8)&gt;&gt;</code>
+    /// from an <code>[fst::Set](fst::Set)</code> that other tooling built, copying its
+    /// bytes instead of rebuilding it.
+    ///
+    /// An external <code>[fst::Set](fst::Set)</code> is valid for segmentation if every
+    /// key is:
+    ///
+    /// * not empty,
+    /// * valid UTF-8, and
+    /// * already canonical according to <code>settings</code>, which for
+    ///   <code>[Settings::new](crate::Settings::new)</code> means in NFD with
+    ///   capitalization preserved.
+    ///
+    /// Every key is checked, and the first one that breaks this rule is returned in
+    /// <code>[Error::InvalidKey](crate::Error::InvalidKey)</code>. The
+    /// <code>[fst::Set](fst::Set)</code> is copied with any values it holds, so every
+    /// word of a set built by <code>[fst::SetBuilder](fst::SetBuilder)</code> weighs zero.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::{Dictionary, Error, Settings};
+    ///
+    /// let set = fst::Set::from_iter(["ice", "just", "justice"]).unwrap();
+    /// let dictionary = Dictionary::from_fst_set(set, Settings::new()).unwrap();
+    ///
+    /// assert_eq!(dictionary.word_segmentations("justice").count(), 2);
+    ///
+    /// let set = fst::Set::from_iter(["caf\u{e9}"]).unwrap();
+    /// assert!(matches!(
+    ///     Dictionary::from_fst_set(set, Settings::new()),
+    ///     Err(Error::InvalidKey { .. })
+    /// ));
+    /// ```
+    #[inline]
+    pub fn from_fst_set<S>(set: fst::Set<S>, settings: Settings) -> Result<Self, Error>
+    where
+        S: AsRef<[u8]>,
+    {
+        Self::from_fst(set.as_fst(), settings)
+    }
+
+    /// Creates a new
+    /// <code>[Dictionary](crate::Dictionary)&lt;[Vec](alloc::vec::Vec)&lt;[u8](core::primitive::u8)&gt;&gt;</code>
+    /// from an <code>[fst::Map](fst::Map)</code> that other tooling built, as with
+    /// <code>[from_fst_set](crate::Dictionary::from_fst_set)</code>.
+    ///
+    /// The values of the <code>[fst::Map](fst::Map)</code> become the weights, or
+    /// payloads, of its words.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::{Dictionary, Settings};
+    ///
+    /// let map = fst::Map::from_iter([("ice", 2), ("just", 7)]).unwrap();
+    /// let dictionary = Dictionary::from_fst_map(map, Settings::new()).unwrap();
+    ///
+    /// assert_eq!(dictionary.weight("just"), Some(7));
+    /// ```
+    #[inline]
+    pub fn from_fst_map<S>(map: fst::Map<S>, settings: Settings) -> Result<Self, Error>
+    where
+        S: AsRef<[u8]>,
+    {
+        Self::from_fst(map.as_fst(), settings)
+    }
+
+    /// Checks the keys of <code>fst</code> and copies its bytes between a header and a
+    /// fingerprint.
+    fn from_fst<S>(fst: &Fst<S>, settings: Settings) -> Result<Self, Error>
+    where
+        S: AsRef<[u8]>,
+    {
+        let mut canonical = String::new();
+        let mut keys = fst.stream();
+        while let Some((key, _)) = keys.next() {
+            let valid = match core::str::from_utf8(key) {
+                Ok(word) if !word.is_empty() => {
+                    canonical.clear();
+                    settings.canonicalize_into(word, &mut canonical);
+                    canonical == word
+                }
+                _ => false,
+            };
+            if !valid {
+                return Err(Error::InvalidKey { key: key.to_vec() });
+            }
+        }
+
+        let fst = fst.as_bytes();
+        let mut bytes = Vec::with_capacity(HEADER_LEN + fst.len() + FOOTER_LEN);
+        bytes.extend_from_slice(&format::encode_header(settings));
+        bytes.extend_from_slice(fst);
+        bytes.extend_from_slice(&format::fingerprint(fst).to_le_bytes());
+        Dictionary::from_bytes(bytes)
+    }
+
+    /// Returns the words of this <code>[Dictionary](crate::Dictionary)</code> and their
+    /// weights as an <code>[fst::Map](fst::Map)</code> for other tooling, without the
+    /// header and fingerprint around it.
+    ///
+    /// The keys are canonical according to this
+    /// <code>[Dictionary](crate::Dictionary)</code>'s
+    /// <code>[Settings](crate::Settings)</code>, so the
+    /// <code>[fst::Map](fst::Map)</code> can be turned back into a
+    /// <code>[Dictionary](crate::Dictionary)</code> with
+    /// <code>[from_fst_map](crate::Dictionary::from_fst_map)</code> and the same
+    /// <code>[Settings](crate::Settings)</code>.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::Dictionary;
+    ///
+    /// let set = fst::Set::from(Dictionary::new(&["just", "ice"]).into_fst().into_fst());
+    ///
+    /// assert!(set.contains("ice"));
+    /// ```
+    pub fn into_fst(self) -> fst::Map<Vec<u8>> {
+        let mut bytes = self.fst.into_inner().0;
+        bytes.truncate(bytes.len() - FOOTER_LEN);
+        bytes.drain(..HEADER_LEN);
+        fst::Map::new(bytes).expect("a dictionary holds a valid fst")
+    }
+}
+
+impl<D> Dictionary<D>
+where
+    D: AsRef<[u8]>,
+{
+    /// Returns the words of this <code>[Dictionary](crate::Dictionary)</code> and their
+    /// weights as an <code>[fst::Map](fst::Map)</code> that borrows its bytes, as with
+    /// <code>[into_fst](crate::Dictionary::into_fst)</code>.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::{Dictionary, Settings};
+    ///
+    /// let dictionary = Dictionary::with_weights([("just", 7), ("ice", 2)], Settings::new());
+    /// let map = dictionary.as_fst();
+    ///
+    /// assert_eq!(map.get("just"), Some(7));
+    /// assert_eq!(map.len(), 2);
+    /// ```
+    #[inline]
+    pub fn as_fst(&self) -> fst::Map<&[u8]> {
+        fst::Map::new(self.fst.as_inner().as_ref()).expect("a dictionary holds a valid fst")
+    }
+}
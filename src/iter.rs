@@ -1,13 +1,14 @@
 //TODO: remove
 #![allow(dead_code)]
 
+use alloc::collections::BTreeMap;
 use alloc::string::String;
 use alloc::sync::Arc;
 use alloc::vec::Vec;
 use bitvec::vec::BitVec;
-use unicode_normalization::UnicodeNormalization;
 use unicode_segmentation::UnicodeSegmentation;
 
+use crate::matcher::WordMatcher;
 use crate::Dictionary;
 
 /// The <code>[Iterator](core::iter::Iterator)</code> that
@@ -94,7 +95,7 @@ impl<'s> WordSegmentations<'s> {
         // Go forward until going forward some more will go past the nth item, then go
         // toward the end of string
         let mut advance_remaining = n;
-        'faux_loop: loop {
+        'forward: {
             // Handle forward-specific subcounts
             for _ in starting_path_index.wrapping_add(1)..self.current_forward_path.len() {
                 let (ref mut starting_node, ref mut unreached_range) =
@@ -126,7 +127,7 @@ impl<'s> WordSegmentations<'s> {
                             .wrapping_add(next_node)
                             .wrapping_sub(*starting_node);
                         *starting_node = next_node;
-                        break 'faux_loop;
+                        break 'forward;
                     } else {
                         advance_remaining =
                             advance_remaining.wrapping_sub(next_node_solution_count.unwrap().get());
@@ -170,7 +171,7 @@ impl<'s> WordSegmentations<'s> {
                     last_forward_segment.1.start =
                         last_forward_segment.1.start.wrapping_add(node_offset);
 
-                    break 'faux_loop;
+                    break 'forward;
                 } else {
                     advance_remaining =
                         advance_remaining.wrapping_sub(next_node_solution_count.unwrap().get());
@@ -208,7 +209,7 @@ impl<'s> WordSegmentations<'s> {
                         let starting_range = unsafe { get_row_bounds(starting_node, self.word_boundary_str_indexes.as_ref().unwrap().len()) };
                         self.current_forward_path.push((starting_node, starting_range));
 
-                        break 'faux_loop;
+                        break 'forward;
                     } else {
                         advance_remaining =
                             advance_remaining.wrapping_sub(next_node_solution_count.unwrap().get());
@@ -227,17 +228,57 @@ impl<'s> WordSegmentations<'s> {
         }
 
         // Create rest of forward path
-        todo!();
-
-        Ok(())
+        todo!()
     }
 
     pub(crate) fn new<D>(dictionary: &Dictionary<D>, input: &'s str) -> Self
     where
         D: AsRef<[u8]>,
     {
-        let dictionary = &dictionary.fst;
-        let dictionary_root = dictionary.root();
+        Self::with_payloads(dictionary, input, None)
+    }
+
+    /// Returns an <code>[Iterator](core::iter::Iterator)</code> over the ways to
+    /// segment <code>input</code> into words of <code>matcher</code>, as with
+    /// <code>[Dictionary](crate::Dictionary)::[word_segmentations](crate::Dictionary::word_segmentations)</code>.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::collections::BTreeSet;
+    /// use wordbreaker::{Settings, WordSegmentations, WordSet};
+    ///
+    /// let words = ["just", "ice", "justice"]
+    ///     .iter()
+    ///     .map(|word| word.to_string())
+    ///     .collect::<BTreeSet<_>>();
+    /// let matcher = WordSet::new(&words, Settings::new()).unwrap();
+    /// let mut word_segmentations =
+    ///     WordSegmentations::from_matcher(matcher, "justice").collect::<Vec<_>>();
+    ///
+    /// word_segmentations.sort_unstable();
+    /// assert_eq!(word_segmentations, [vec!["just", "ice"], vec!["justice"]]);
+    /// ```
+    #[inline]
+    pub fn from_matcher<M>(matcher: M, input: &'s str) -> Self
+    where
+        M: WordMatcher,
+    {
+        Self::with_payloads(matcher, input, None)
+    }
+
+    /// Like <code>[new](WordSegmentations::new)</code>, but also inserts the payload of
+    /// every word found in <code>input</code> into <code>payloads</code>, keyed by the
+    /// word's byte range in <code>input</code>.
+    pub(crate) fn with_payloads<M>(
+        matcher: M,
+        input: &'s str,
+        mut payloads: Option<&mut BTreeMap<(usize, usize), u64>>,
+    ) -> Self
+    where
+        M: WordMatcher,
+    {
+        let settings = matcher.settings();
 
         if input.is_empty() {
             Self {
@@ -249,12 +290,24 @@ impl<'s> WordSegmentations<'s> {
                 current_reverse_path: vec![(0, 0..0)],
             }
         } else {
-            let input_nfd = input.nfd().collect::<String>();
-            let input_nfd_grapheme_indexes = input_nfd
-                .grapheme_indices(true)
-                .map(|(position, _)| position)
-                .chain(core::iter::once(input_nfd.len()))
+            // Canonicalize the input one grapheme at a time so that its graphemes line
+            // up with those of the original input even when case folding changes their
+            // lengths.
+            let mut input_nfd = String::with_capacity(input.len());
+            let input_nfd_grapheme_indexes = core::iter::once(0)
+                .chain(input.graphemes(true).map(|grapheme| {
+                    settings.canonicalize_into(grapheme, &mut input_nfd);
+                    input_nfd.len()
+                }))
                 .collect::<Vec<_>>();
+            let input_grapheme_indexes = match payloads {
+                Some(_) => input
+                    .grapheme_indices(true)
+                    .map(|(position, _)| position)
+                    .chain(core::iter::once(input.len()))
+                    .collect::<Vec<_>>(),
+                None => Vec::new(),
+            };
             let old_node_count = input_nfd_grapheme_indexes.len();
             let old_last_node_index = old_node_count.wrapping_sub(1);
             let old_edge_count = old_node_count
@@ -276,7 +329,7 @@ impl<'s> WordSegmentations<'s> {
                         println!("old_solutions_tree: {old_solutions_tree}");
             */
             while let Some(starting_node) = remaining_word_starts.first_one() {
-                let mut cursor_node = dictionary_root;
+                let mut cursor = matcher.start();
 
                 let ending_nodes = unsafe {
                     old_solutions_tree.get_mut/*_unchecked*/(get_row_bounds/*_unchecked*/(
@@ -306,18 +359,11 @@ impl<'s> WordSegmentations<'s> {
                 {
                     /*                    println!("ending_node_offset_minus_one: {ending_node_offset_minus_one}");
                     println!("input_nfd_grapheme: {input_nfd_grapheme}");*/
-                    for byte in input_nfd_grapheme.bytes() {
-                        if let Some(transition_index) = cursor_node.find_input(byte) {
-                            //println!("byte: {byte} (accepted)");
-                            cursor_node =
-                                dictionary.node(cursor_node.transition_addr(transition_index));
-                        } else {
-                            //println!("byte: {byte} (rejected)");
-                            break 'words_search;
-                        }
+                    if !matcher.advance(&mut cursor, input_nfd_grapheme.as_bytes()) {
+                        break 'words_search;
                     }
 
-                    if cursor_node.is_final() {
+                    if let Some(payload) = matcher.word_end(&cursor) {
                         //println!("input_nfd_grapheme ends a word");
                         unsafe {
                             ending_nodes.set/*_unchecked*/(ending_node_offset_minus_one, true);
@@ -328,6 +374,15 @@ impl<'s> WordSegmentations<'s> {
                         unsafe {
                             remaining_word_starts.set/*_unchecked*/(ending_node, true);
                         }
+                        if let Some(ref mut payloads) = payloads {
+                            payloads.insert(
+                                (
+                                    input_grapheme_indexes[starting_node],
+                                    input_grapheme_indexes[ending_node],
+                                ),
+                                payload,
+                            );
+                        }
                         //println!("ending_nodes: {ending_nodes}");
                         //println!("ending_node: {ending_node}");
                     }
@@ -375,7 +430,7 @@ impl<'s> WordSegmentations<'s> {
                             ending_nodes.set/*_unchecked*/(node_to_delete_offset_minus_one, false);
                         }
                     }
-                    if ending_nodes.is_empty() {
+                    if ending_nodes.not_any() {
                         unsafe {
                             deleted_starting_nodes.set/*_unchecked*/(starting_node, true);
                         }
@@ -609,8 +664,7 @@ impl<'s> DoubleEndedIterator for WordSegmentations<'s> {
             let iter = self
                 .current_reverse_path
                 .iter()
-                .cloned()
-                .map(|(input_index, _)| input_index);
+                .map(|&(input_index, _)| input_index);
             // println!("iter.clone().collect::<Vec<_>>(): {:?}", iter.clone().collect::<Vec<_>>());
 
             let result = core::iter::once(0)
@@ -635,8 +689,7 @@ impl<'s> DoubleEndedIterator for WordSegmentations<'s> {
             if iter.eq(self
                 .current_forward_path
                 .iter()
-                .cloned()
-                .map(|(input_index, _)| input_index))
+                .map(|&(input_index, _)| input_index))
             {
                 self.word_boundary_str_indexes = None;
                 self.solutions_tree = None;
@@ -768,8 +821,7 @@ impl<'s> Iterator for WordSegmentations<'s> {
             let iter = self
                 .current_forward_path
                 .iter()
-                .cloned()
-                .map(|(input_index, _)| input_index);
+                .map(|&(input_index, _)| input_index);
             //println!("iter.clone().collect::<Vec<_>>(): {:?}", iter.clone().collect::<Vec<_>>());
 
             let result = core::iter::once(0)
@@ -794,8 +846,7 @@ impl<'s> Iterator for WordSegmentations<'s> {
             if iter.eq(self
                 .current_reverse_path
                 .iter()
-                .cloned()
-                .map(|(input_index, _)| input_index))
+                .map(|&(input_index, _)| input_index))
             {
                 self.word_boundary_str_indexes = None;
                 self.solutions_tree = None;
@@ -867,17 +918,8 @@ impl<'s> Iterator for WordSegmentations<'s> {
     }*/
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        // If there are no nonempty solutions
-        if self.solutions_tree.is_none() {
-            // If there are no solutions
-            if self.current_forward_path.is_empty() {
-                (0, Some(0))
-            // If there is one empty-Vec solution
-            } else {
-                (1, Some(1))
-            }
         // If there is at least one nonempty solution
-        } else {
+        if let Some(solutions_tree) = self.solutions_tree.as_ref() {
             let mut total_solution_count = 0_usize;
 
             let mut starting_path_index = 0;
@@ -898,7 +940,7 @@ impl<'s> Iterator for WordSegmentations<'s> {
                             break unsafe {
                                 // Get the index range in the solution tree between the
                                 // forward and reverse paths
-                                self.solutions_tree.as_ref().unwrap().get/*_unchecked*/(forward_range.start..reverse_range.end).unwrap()
+                                solutions_tree.get/*_unchecked*/(forward_range.start..reverse_range.end).unwrap()
                             }
                             // Go through the solution tree indexes that represent the
                             // next node in the solution
@@ -928,7 +970,7 @@ impl<'s> Iterator for WordSegmentations<'s> {
             {
                 let starting_node_plus_one = starting_node.wrapping_add(1);
                 for unreached_node in unsafe {
-                    self.solutions_tree.as_ref().unwrap().get/*_unchecked*/(unreached_range).unwrap()
+                    solutions_tree.get/*_unchecked*/(unreached_range).unwrap()
                 }
                 .iter_ones()
                 .map(|offset_minus_one| {
@@ -989,7 +1031,7 @@ impl<'s> Iterator for WordSegmentations<'s> {
                     .wrapping_sub(unreached_range.end);
 
                 for unreached_node in unsafe {
-                    self.solutions_tree.as_ref().unwrap().get/*_unchecked*/(unreached_range).unwrap()
+                    solutions_tree.get/*_unchecked*/(unreached_range).unwrap()
                 }
                 .iter_ones()
                 .map(|offset_minus_one| {
@@ -1015,7 +1057,16 @@ impl<'s> Iterator for WordSegmentations<'s> {
                 None => return (usize::MAX, None),
             };
 
-            return (total_solution_count, Some(total_solution_count));
+            (total_solution_count, Some(total_solution_count))
+        // If there are no nonempty solutions
+        } else {
+            // If there are no solutions
+            if self.current_forward_path.is_empty() {
+                (0, Some(0))
+            // If there is one empty-Vec solution
+            } else {
+                (1, Some(1))
+            }
         }
     }
 }
@@ -0,0 +1,267 @@
+use alloc::vec::Vec;
+use fst::raw::{Node, Output};
+
+use crate::matcher::{self, WordMatcher};
+use crate::{
+    Dictionary, Settings, SettingsMismatch, WordSegmentations, WordSegmentationsWithPayloads,
+};
+
+/// What a layer of a <code>[LayeredDictionary](crate::LayeredDictionary)</code> does to
+/// the words of the layers below it.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Layer {
+    /// The words of the layer are added.
+    Additions,
+    /// The words of the layer are hidden.
+    Removals,
+}
+
+/// A stack of <code>[Dictionary](crate::Dictionary)</code>s that are searched together
+/// at query time, without merging them into one.
+///
+/// The bottom layer is a base <code>[Dictionary](crate::Dictionary)</code>. Each layer
+/// pushed on top of it either adds its words or hides them from the layers below, and
+/// a word is in the <code>[LayeredDictionary](crate::LayeredDictionary)</code> if the
+/// highest layer that contains it adds it. This makes it cheap to put a small user
+/// dictionary on top of a large shared one.
+///
+/// # Examples
+///
+/// ```rust
+/// use wordbreaker::{Dictionary, LayeredDictionary};
+///
+/// let base = Dictionary::new(&["just", "ice", "justice", "hello"]);
+/// let mut dictionary = LayeredDictionary::new(base);
+/// dictionary.push_removals(Dictionary::new(&["justice"])).unwrap();
+/// dictionary.push_additions(Dictionary::new(&["cream"])).unwrap();
+///
+/// assert_eq!(
+///     dictionary.word_segmentations("justicecream").collect::<Vec<_>>(),
+///     [vec!["just", "ice", "cream"]]
+/// );
+/// ```
+#[derive(Clone)]
+pub struct LayeredDictionary<D> {
+    settings: Settings,
+    layers: Vec<(Layer, Dictionary<D>)>,
+}
+
+impl<D> LayeredDictionary<D>
+where
+    D: AsRef<[u8]>,
+{
+    /// Creates a new <code>[LayeredDictionary](crate::LayeredDictionary)</code> with
+    /// <code>base</code> as its only layer.
+    #[inline]
+    pub fn new(base: Dictionary<D>) -> Self {
+        Self {
+            settings: base.settings,
+            layers: vec![(Layer::Additions, base)],
+        }
+    }
+
+    /// Pushes a layer on top that adds the words of <code>dictionary</code>, including
+    /// any that lower layers hide.
+    ///
+    /// The words of every layer are compared without being canonicalized again, so all
+    /// layers must have the same <code>[Settings](crate::Settings)</code>.
+    #[inline]
+    pub fn push_additions(&mut self, dictionary: Dictionary<D>) -> Result<(), SettingsMismatch> {
+        self.push(Layer::Additions, dictionary)
+    }
+
+    /// Pushes a layer on top that hides the words of <code>dictionary</code> from the
+    /// layers below it.
+    ///
+    /// All layers must have the same <code>[Settings](crate::Settings)</code>, except
+    /// that the <code>[ValueKind](crate::ValueKind)</code> of a layer of removals, whose
+    /// values are never used, does not matter.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::{Dictionary, LayeredDictionary};
+    ///
+    /// let mut dictionary = LayeredDictionary::new(Dictionary::new(&["just", "ice"]));
+    /// dictionary.push_removals(Dictionary::new(&["ice"])).unwrap();
+    ///
+    /// assert!(dictionary.contains("just"));
+    /// assert!(!dictionary.contains("ice"));
+    /// ```
+    #[inline]
+    pub fn push_removals(&mut self, dictionary: Dictionary<D>) -> Result<(), SettingsMismatch> {
+        self.push(Layer::Removals, dictionary)
+    }
+
+    fn push(&mut self, layer: Layer, dictionary: Dictionary<D>) -> Result<(), SettingsMismatch> {
+        let matches = match layer {
+            Layer::Additions => dictionary.settings == self.settings,
+            Layer::Removals => dictionary.settings.canonicalizes_like(&self.settings),
+        };
+        if !matches {
+            return Err(SettingsMismatch {
+                left: self.settings,
+                right: dictionary.settings,
+            });
+        }
+
+        self.layers.push((layer, dictionary));
+        Ok(())
+    }
+
+    /// Returns an <code>[Iterator](core::iter::Iterator)</code> over the layers, from
+    /// the base upward.
+    #[inline]
+    pub fn layers(&self) -> impl DoubleEndedIterator<Item = (Layer, &Dictionary<D>)> + '_ {
+        self.layers
+            .iter()
+            .map(|(layer, dictionary)| (*layer, dictionary))
+    }
+
+    /// Returns the <code>[Settings](crate::Settings)</code> shared by every layer.
+    #[inline(always)]
+    pub fn settings(&self) -> Settings {
+        self.settings
+    }
+
+    /// Returns whether <code>word</code> is in this
+    /// <code>[LayeredDictionary](crate::LayeredDictionary)</code>, after canonicalizing it
+    /// according to its <code>[Settings](crate::Settings)</code>.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::{Dictionary, LayeredDictionary};
+    ///
+    /// let mut dictionary = LayeredDictionary::new(Dictionary::new(&["just", "ice"]));
+    /// dictionary.push_additions(Dictionary::new(&["justice"])).unwrap();
+    ///
+    /// assert!(dictionary.contains("justice"));
+    /// assert!(!dictionary.contains("hello"));
+    /// ```
+    pub fn contains(&self, word: &str) -> bool {
+        if word.is_empty() {
+            return false;
+        }
+
+        let word = self.settings.canonicalize(word);
+        self.layers
+            .iter()
+            .rev()
+            .find(|(_, dictionary)| dictionary.fst.contains_key(&word))
+            .is_some_and(|(layer, _)| *layer == Layer::Additions)
+    }
+
+    /// Returns an <code>[Iterator](core::iter::Iterator)</code> over the ways to
+    /// segment <code>input</code> into words of this
+    /// <code>[LayeredDictionary](crate::LayeredDictionary)</code>, as with
+    /// <code>[Dictionary](crate::Dictionary)::[word_segmentations](crate::Dictionary::word_segmentations)</code>.
+    ///
+    /// Every layer is searched at once, so no merged
+    /// <code>[Dictionary](crate::Dictionary)</code> is built.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::{Dictionary, LayeredDictionary};
+    ///
+    /// let mut dictionary = LayeredDictionary::new(Dictionary::new(&["just", "ice"]));
+    /// dictionary.push_additions(Dictionary::new(&["justice"])).unwrap();
+    /// let mut word_segmentations = dictionary
+    ///     .word_segmentations("justice")
+    ///     .collect::<Vec<_>>();
+    ///
+    /// word_segmentations.sort_unstable();
+    /// assert_eq!(word_segmentations, [vec!["just", "ice"], vec!["justice"]]);
+    /// ```
+    #[inline]
+    pub fn word_segmentations<'s>(&self, input: &'s str) -> WordSegmentations<'s> {
+        WordSegmentations::with_payloads(self, input, None)
+    }
+
+    /// Returns an <code>[Iterator](core::iter::Iterator)</code> over the segmentations
+    /// of <code>input</code> with the payload of each word, as with
+    /// <code>[Dictionary](crate::Dictionary)::[word_segmentations_with_payloads](crate::Dictionary::word_segmentations_with_payloads)</code>.
+    ///
+    /// A word's payload comes from the highest layer that contains it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::{Dictionary, LayeredDictionary, Settings};
+    ///
+    /// let base = Dictionary::with_payloads([("just", 1), ("ice", 2)], Settings::new());
+    /// let mut dictionary = LayeredDictionary::new(base);
+    /// dictionary
+    ///     .push_additions(Dictionary::with_payloads([("ice", 20)], Settings::new()))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     dictionary
+    ///         .word_segmentations_with_payloads("justice")
+    ///         .collect::<Vec<_>>(),
+    ///     [vec![("just", 1), ("ice", 20)]]
+    /// );
+    /// ```
+    #[inline]
+    pub fn word_segmentations_with_payloads<'s>(
+        &self,
+        input: &'s str,
+    ) -> WordSegmentationsWithPayloads<'s> {
+        WordSegmentationsWithPayloads::from_matcher(self, input)
+    }
+}
+
+impl<'a, D> WordMatcher for &'a LayeredDictionary<D>
+where
+    D: AsRef<[u8]>,
+{
+    /// The search state of each layer, or
+    /// <code>[None](core::option::Option::None)</code> once no word of that layer
+    /// continues with the graphemes matched so far.
+    type State = Vec<Option<(Node<'a>, Output)>>;
+
+    #[inline(always)]
+    fn settings(&self) -> Settings {
+        self.settings
+    }
+
+    #[inline]
+    fn start(&self) -> Self::State {
+        self.layers
+            .iter()
+            .map(|(_, dictionary)| Some((dictionary.fst.root(), Output::zero())))
+            .collect()
+    }
+
+    fn advance(&self, state: &mut Self::State, grapheme: &[u8]) -> bool {
+        let mut any_additions = false;
+        for ((layer, dictionary), layer_state) in self.layers.iter().zip(state.iter_mut()) {
+            if let Some(ref mut search) = *layer_state {
+                if matcher::advance(dictionary, search, grapheme) {
+                    any_additions |= *layer == Layer::Additions;
+                } else {
+                    *layer_state = None;
+                }
+            }
+        }
+
+        any_additions
+    }
+
+    fn word_end(&self, state: &Self::State) -> Option<u64> {
+        self.layers
+            .iter()
+            .zip(state.iter())
+            .rev()
+            .find_map(|((layer, _), layer_state)| {
+                let payload = matcher::word_end(layer_state.as_ref()?)?;
+                Some((*layer, payload))
+            })
+            .and_then(|(layer, payload)| match layer {
+                Layer::Additions => Some(payload),
+                Layer::Removals => None,
+            })
+    }
+}
@@ -11,15 +11,90 @@ into words from a given dictionary.
 #[macro_use]
 extern crate alloc;
 
+mod aliases;
+#[doc(inline)]
+pub use aliases::{AliasedDictionary, WordSegmentationsWithAliases};
+
+mod blocklist;
+
+mod builder;
+#[doc(inline)]
+pub use builder::DictionaryBuilder;
+
 mod dict;
 #[doc(inline)]
 pub use dict::{Dictionary, Error};
 
+mod filter;
+#[doc(inline)]
+pub use filter::{CaseFilter, CharacterClasses, WordFilter};
+
+mod format;
+
+mod fuzzy;
+#[doc(inline)]
+pub use fuzzy::FuzzyMatches;
+
+mod interop;
+
 mod iter;
 #[doc(inline)]
 pub use iter::WordSegmentations;
 
+mod layered;
+#[doc(inline)]
+pub use layered::{Layer, LayeredDictionary};
+
+#[cfg(feature = "std")]
+mod load;
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use load::{compile_word_list, compile_word_list_to};
+
+mod matcher;
+#[doc(inline)]
+pub use matcher::{WordMatcher, WordSet};
+
+#[cfg(feature = "mmap")]
+mod mmap;
+
+mod mutable;
+#[doc(inline)]
+pub use mutable::{Compacted, Compaction, MutableDictionary};
+
+mod ops;
+#[doc(inline)]
+pub use ops::SettingsMismatch;
+
+mod payloads;
+#[doc(inline)]
+pub use payloads::WordSegmentationsWithPayloads;
+
+mod report;
+#[doc(inline)]
+pub use report::{BuildReport, MergedWords, Suspicion, SuspiciousWord};
+
+mod search;
+#[doc(inline)]
+pub use search::{Matches, Pattern, PatternError};
+
+#[cfg(feature = "serde")]
+mod serialize;
+
+mod settings;
+#[doc(inline)]
+pub use settings::{CaseFolding, NormalizationForm, Settings, ValueKind};
+
+mod stats;
+#[doc(inline)]
+pub use stats::{Lengths, Stats};
+
+mod words;
+#[doc(inline)]
+pub use words::{Words, WordsWithPrefix};
+
 #[cfg(test)]
+#[allow(clippy::iter_nth_zero)]
 mod tests {
     use super::*;
 
@@ -69,6 +144,330 @@ mod tests {
         assert_eq!(word_segmentations.clone().nth_back(4), None);
     }
 
+    #[test]
+    fn aliases_test() {
+        let settings = Settings::new().with_case_folding(CaseFolding::Full);
+        let dictionary = AliasedDictionary::new(
+            ["the", "colour", "night", "nit", "e"],
+            [("colour", "color"), ("nite", "nyte"), ("NITE", "night")],
+            settings,
+        );
+
+        assert_eq!(dictionary.canonical_forms(), ["color", "night"]);
+        assert_eq!(dictionary.canonical_form("Colour"), Some("color"));
+        assert_eq!(dictionary.canonical_form("color"), None);
+        assert!(dictionary.dictionary().contains("nyte"));
+        assert_eq!(
+            dictionary
+                .word_segmentations_with_aliases("TheColourColor")
+                .collect::<Vec<_>>(),
+            [vec![
+                ("The", "The"),
+                ("Colour", "color"),
+                ("Color", "Color")
+            ]]
+        );
+        assert_eq!(
+            dictionary
+                .word_segmentations_with_aliases("nite")
+                .collect::<Vec<_>>(),
+            [vec![("nit", "nit"), ("e", "e")], vec![("nite", "night")]]
+        );
+        assert_eq!(
+            dictionary
+                .word_segmentations_with_aliases("nite")
+                .rev()
+                .collect::<Vec<_>>(),
+            [vec![("nite", "night")], vec![("nit", "nit"), ("e", "e")]]
+        );
+    }
+
+    #[test]
+    fn blocklist_test() {
+        let dictionary = Dictionary::new(&["ab", "abc", "cd", "def", "abcd", "ef", "c"]);
+        let blocklist = Dictionary::new(&["ab", "cd", "xyz"]);
+        let without = Dictionary::new(&["abc", "def", "abcd", "ef", "c"]);
+        let word_segmentations = dictionary
+            .word_segmentations_excluding("abcdef", &blocklist)
+            .unwrap();
+        let expected = without.word_segmentations("abcdef");
+
+        assert_eq!(
+            word_segmentations.clone().collect::<Vec<_>>(),
+            [vec!["abc", "def"], vec!["abcd", "ef"]]
+        );
+        assert!(word_segmentations.clone().eq(expected.clone()));
+        assert!(word_segmentations.clone().rev().eq(expected.clone().rev()));
+        assert_eq!(word_segmentations.size_hint(), expected.size_hint());
+        assert_eq!(word_segmentations.clone().count(), 2);
+        assert_eq!(word_segmentations.clone().nth(1), Some(vec!["abcd", "ef"]));
+
+        let everything = Dictionary::new(&["ab", "abc", "cd", "def", "abcd", "ef", "c"]);
+        assert_eq!(
+            dictionary
+                .word_segmentations_excluding("abcdef", &everything)
+                .unwrap()
+                .next(),
+            None
+        );
+
+        let settings = Settings::new().with_case_folding(CaseFolding::Full);
+        assert!(dictionary
+            .word_segmentations_excluding("abcdef", &Dictionary::with_settings(["ab"], settings))
+            .is_err());
+    }
+
+    #[test]
+    fn build_report_test() {
+        let settings = Settings::new().with_case_folding(CaseFolding::Full);
+        let (dictionary, report) = Dictionary::with_report(
+            [
+                "Ice", "just", "", "ICE", "ice", "just", "", "a\tb", "\u{308}o", "justice",
+            ],
+            settings,
+        );
+
+        assert!(dictionary
+            .words()
+            .eq(["a\tb", "ice", "just", "justice", "\u{308}o"]));
+        assert_eq!(report.empty_words, [2, 6]);
+        assert_eq!(
+            report.merged_words,
+            [MergedWords {
+                word: "ice".to_string(),
+                spellings: vec!["Ice".to_string(), "ICE".to_string(), "ice".to_string()],
+            }]
+        );
+        assert_eq!(
+            report.suspicious_words,
+            [
+                SuspiciousWord {
+                    index: 7,
+                    word: "a\tb".to_string(),
+                    suspicions: vec![Suspicion::Whitespace, Suspicion::Control],
+                },
+                SuspiciousWord {
+                    index: 8,
+                    word: "\u{308}o".to_string(),
+                    suspicions: vec![Suspicion::PartialGrapheme],
+                },
+            ]
+        );
+        assert!(!report.is_empty());
+
+        let (_, report) = Dictionary::with_report(["just", "ice", "justice"], settings);
+        assert!(report.is_empty());
+    }
+
+    #[test]
+    fn byte_format_test() {
+        let settings = Settings::new().with_case_folding(CaseFolding::Full);
+        let dictionary = Dictionary::with_weights([("just", 1), ("ice", 2)], settings);
+        let bytes = dictionary.as_bytes().to_vec();
+
+        assert_eq!(&bytes[..8], b"WBRKDICT");
+        let dictionary = Dictionary::from_bytes_verified(&bytes[..]).unwrap();
+        assert_eq!(dictionary.settings(), settings);
+        assert_eq!(dictionary.weight("ICE"), Some(2));
+
+        assert!(matches!(
+            Dictionary::from_bytes(&bytes[..10]),
+            Err(Error::NotADictionary)
+        ));
+        let mut wrong_magic = bytes.clone();
+        wrong_magic[0] = b'X';
+        assert!(matches!(
+            Dictionary::from_bytes(wrong_magic),
+            Err(Error::NotADictionary)
+        ));
+        let mut wrong_version = bytes.clone();
+        wrong_version[8] = 3;
+        assert!(matches!(
+            Dictionary::from_bytes(wrong_version),
+            Err(Error::UnsupportedVersion {
+                expected: 2,
+                got: 3
+            })
+        ));
+        let mut wrong_settings = bytes.clone();
+        wrong_settings[10] = 9;
+        assert!(matches!(
+            Dictionary::from_bytes(wrong_settings),
+            Err(Error::InvalidSettings)
+        ));
+        let mut wrong_value_kind = bytes.clone();
+        wrong_value_kind[12] = 9;
+        assert!(matches!(
+            Dictionary::from_bytes(wrong_value_kind),
+            Err(Error::InvalidSettings)
+        ));
+        let mut wrong_fingerprint = bytes.clone();
+        *wrong_fingerprint.last_mut().unwrap() ^= 1;
+        assert!(Dictionary::from_bytes(&wrong_fingerprint[..]).is_ok());
+        assert!(matches!(
+            Dictionary::from_bytes_verified(&wrong_fingerprint[..]),
+            Err(Error::FingerprintMismatch { .. })
+        ));
+
+        assert_eq!(Dictionary::migrate(&bytes).unwrap(), bytes);
+        let v1_bytes = [&b"WBRKDICT\x01\x00"[..], &bytes[10..12], &bytes[13..]].concat();
+        assert!(matches!(
+            Dictionary::from_bytes(&v1_bytes[..]),
+            Err(Error::UnsupportedVersion {
+                expected: 2,
+                got: 1
+            })
+        ));
+        assert_eq!(Dictionary::migrate(&v1_bytes).unwrap(), bytes);
+        let old_bytes = fst::Map::from_iter([("cafe\u{301}", 3), ("ice", 4)])
+            .unwrap()
+            .into_fst()
+            .into_inner();
+        let migrated =
+            Dictionary::from_bytes_verified(Dictionary::migrate(&old_bytes).unwrap()).unwrap();
+        assert_eq!(migrated.settings(), Settings::new());
+        assert_eq!(migrated.weight("café"), Some(3));
+        assert_eq!(migrated.len(), 2);
+        assert!(matches!(
+            Dictionary::migrate(b"not a dictionary"),
+            Err(Error::NotADictionary)
+        ));
+    }
+
+    #[test]
+    fn case_folding_test() {
+        let settings = Settings::new().with_case_folding(CaseFolding::Full);
+        let dictionary = Dictionary::with_settings(["Arrow", "box", "straße"], settings);
+
+        assert_eq!(
+            dictionary
+                .word_segmentations("arrowBOX")
+                .collect::<Vec<_>>(),
+            [vec!["arrow", "BOX"]]
+        );
+        assert_eq!(
+            dictionary
+                .word_segmentations("STRASSEbox")
+                .collect::<Vec<_>>(),
+            [vec!["STRASSE", "box"]]
+        );
+
+        let dictionary = Dictionary::from_bytes(dictionary.as_bytes()).unwrap();
+        assert_eq!(dictionary.settings(), settings);
+        assert_eq!(
+            dictionary
+                .word_segmentations("ArRoWbOx")
+                .collect::<Vec<_>>(),
+            [vec!["ArRoW", "bOx"]]
+        );
+    }
+
+    #[test]
+    fn case_preserving_test() {
+        let dictionary = Dictionary::new(&["Arrow", "box"]);
+
+        assert_eq!(dictionary.settings().case_folding(), CaseFolding::Preserve);
+        assert_eq!(dictionary.word_segmentations("arrowbox").count(), 0);
+        assert_eq!(
+            dictionary
+                .word_segmentations("Arrowbox")
+                .collect::<Vec<_>>(),
+            [vec!["Arrow", "box"]]
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn compile_word_list_test() {
+        let out_dir = tempfile::tempdir().unwrap();
+        let settings = Settings::new().with_case_folding(CaseFolding::Full);
+        compile_word_list_to(out_dir.path(), "american-english-dictionary.txt", settings).unwrap();
+
+        let bytes =
+            std::fs::read(out_dir.path().join("american-english-dictionary.txt.dict")).unwrap();
+        let dictionary = Dictionary::from_bytes_verified(&bytes[..]).unwrap();
+        assert_eq!(
+            dictionary.as_bytes(),
+            Dictionary::from_path("american-english-dictionary.txt", settings)
+                .unwrap()
+                .as_bytes()
+        );
+
+        assert!(matches!(
+            compile_word_list_to(out_dir.path(), "missing.txt", settings),
+            Err(Error::Io(_))
+        ));
+        for word_list in [
+            "../american-english-dictionary.txt",
+            "fixtures/../american-english-dictionary.txt",
+            "/etc/hostname",
+            "",
+        ] {
+            match compile_word_list_to(out_dir.path(), word_list, settings) {
+                Err(Error::Io(e)) => {
+                    assert_eq!(
+                        e.kind(),
+                        std::io::ErrorKind::InvalidInput,
+                        "{:?}",
+                        word_list
+                    )
+                }
+                other => panic!("unexpected result for {:?}: {:?}", word_list, other),
+            }
+        }
+
+        // A word list that fails partway doesn't leave a partial dictionary behind.
+        assert!(matches!(
+            compile_word_list_to(out_dir.path(), "fixtures/invalid-utf8.txt", settings),
+            Err(Error::InvalidUtf8 { line: 2, .. })
+        ));
+        assert!(!out_dir
+            .path()
+            .join("fixtures/invalid-utf8.txt.dict")
+            .exists());
+
+        // compile_word_list only works in a build script.
+        if std::env::var_os("OUT_DIR").is_none() {
+            assert!(matches!(
+                compile_word_list("american-english-dictionary.txt", settings),
+                Err(Error::Io(_))
+            ));
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn include_dictionary_test() {
+        // fixtures/words.txt.dict was compiled with compile_word_list_to, and must be
+        // compiled again whenever the byte format changes.
+        let dictionary = __include_dictionary_in!(env!("CARGO_MANIFEST_DIR"), "fixtures/words.txt");
+
+        let out_dir = tempfile::tempdir().unwrap();
+        compile_word_list_to(out_dir.path(), "fixtures/words.txt", Settings::new()).unwrap();
+        let bytes = std::fs::read(out_dir.path().join("fixtures/words.txt.dict")).unwrap();
+        assert_eq!(dictionary.as_bytes(), bytes);
+
+        assert!(dictionary
+            .words()
+            .eq(["Cafe\u{301}", "hello", "ice", "just", "justice"]));
+        assert_eq!(dictionary.word_segmentations("justice").count(), 2);
+    }
+
+    #[test]
+    fn contains_test() {
+        let settings = Settings::new().with_case_folding(CaseFolding::Full);
+        let dictionary = Dictionary::with_settings(["Café", "just", "ice"], settings);
+
+        assert!(dictionary.contains("café"));
+        assert!(dictionary.contains("CAFE\u{301}"));
+        assert!(dictionary.contains("JUST"));
+        assert!(!dictionary.contains("justice"));
+        assert!(!dictionary.contains(""));
+        assert_eq!(dictionary.len(), 3);
+        assert!(!dictionary.is_empty());
+        assert!(Dictionary::new::<&str>(&[]).is_empty());
+    }
+
     #[test]
     fn count_matches_repeated_next_back_test() {
         let dictionary = include_str!("../american-english-dictionary.txt")
@@ -97,6 +496,52 @@ mod tests {
         assert_eq!(count, next_count);
     }
 
+    #[test]
+    fn dead_end_test() {
+        let dictionary = Dictionary::new(&["ab"]);
+        let word_segmentations = dictionary.word_segmentations("abab");
+
+        assert_eq!(
+            word_segmentations.clone().collect::<Vec<_>>(),
+            [vec!["ab", "ab"]]
+        );
+        assert_eq!(
+            word_segmentations.clone().rev().collect::<Vec<_>>(),
+            [vec!["ab", "ab"]]
+        );
+        assert_eq!(word_segmentations.count(), 1);
+    }
+
+    #[test]
+    fn dictionary_builder_sorted_test() {
+        let mut builder = DictionaryBuilder::memory(Settings::new());
+        builder
+            .extend(["", "hello", "ice", "ice", "just", "justice"])
+            .unwrap();
+        let dictionary = builder.into_dictionary().unwrap();
+
+        assert_eq!(
+            dictionary.as_bytes(),
+            Dictionary::new(&["hello", "just", "ice", "justice"]).as_bytes()
+        );
+
+        let mut builder = DictionaryBuilder::new(Vec::new(), Settings::new()).unwrap();
+        builder.insert("just").unwrap();
+        assert!(builder.insert("ice").is_err());
+    }
+
+    #[test]
+    fn dictionary_builder_unsorted_test() {
+        let words = include_str!("../american-english-dictionary.txt").lines();
+        let mut builder = DictionaryBuilder::new(Vec::new(), Settings::new())
+            .unwrap()
+            .with_max_run_bytes(1 << 16);
+        builder.extend_unsorted(words.clone().rev()).unwrap();
+        let bytes = builder.finish().unwrap();
+
+        assert_eq!(bytes, words.collect::<Dictionary<_>>().as_bytes());
+    }
+
     #[test]
     fn empty_input_test() {
         let dictionary = Dictionary::new(&["b"]);
@@ -126,6 +571,135 @@ mod tests {
         assert_eq!(word_segmentations.clone().nth_back(1), None);
     }
 
+    #[test]
+    fn error_test() {
+        let dictionary = Dictionary::try_new(&["just", "ice", "justice"]).unwrap();
+        assert!(Dictionary::try_from_iter(dictionary.words())
+            .unwrap()
+            .words()
+            .eq(dictionary.words()));
+
+        let mut builder = DictionaryBuilder::memory(Settings::new());
+        builder.insert("just").unwrap();
+        match builder.insert("ice") {
+            Err(Error::WordOutOfOrder { previous, got }) => {
+                assert_eq!((previous.as_str(), got.as_str()), ("just", "ice"))
+            }
+            _ => panic!("expected Error::WordOutOfOrder"),
+        }
+
+        assert_eq!(Dictionary::new(&["just", "\u{301}"]).len(), 2);
+        match Dictionary::try_new(&["just", "\u{301}"]) {
+            Err(Error::InvalidWord { word }) => assert_eq!(word, "\u{301}"),
+            _ => panic!("expected Error::InvalidWord"),
+        }
+        assert!(matches!(
+            Dictionary::try_with_weights([("\u{308}ice", 1)], Settings::new()),
+            Err(Error::InvalidWord { .. })
+        ));
+        assert!(matches!(
+            Dictionary::try_with_payloads([("\u{301}", 1)], Settings::new()),
+            Err(Error::InvalidWord { .. })
+        ));
+        assert!(matches!(
+            Pattern::regex("a(").map_err(Error::from),
+            Err(Error::Pattern(PatternError::UnclosedGroup { position: 1 }))
+        ));
+
+        let bytes = dictionary.as_bytes();
+        let mut wrong_checksum = bytes.to_vec();
+        wrong_checksum[bytes.len() - 9] ^= 1;
+        assert!(Dictionary::from_bytes(&wrong_checksum[..]).is_ok());
+        assert!(matches!(
+            Dictionary::from_bytes_verified(&wrong_checksum[..]),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+
+        let mut corrupt = bytes[..12].to_vec();
+        corrupt.extend_from_slice(&[0; 11]);
+        assert!(matches!(
+            Dictionary::from_bytes(&corrupt[..]),
+            Err(Error::Fst(_))
+        ));
+        assert!(matches!(Dictionary::migrate(&corrupt), Err(Error::Fst(_))));
+    }
+
+    #[test]
+    fn filter_test() {
+        use unicode_segmentation::UnicodeSegmentation;
+
+        let words = include_str!("../american-english-dictionary.txt").lines();
+        let filter = WordFilter::new()
+            .with_min_graphemes(2)
+            .with_max_graphemes(10)
+            .with_allowed_characters(CharacterClasses::ALPHABETIC | CharacterClasses::MARKS)
+            .with_case_filter(CaseFilter::RejectUppercase)
+            .with_predicate(|word| !word.starts_with('z'));
+        let dictionary = Dictionary::with_filter(words.clone(), Settings::new(), filter.clone());
+
+        assert_eq!(
+            dictionary.len(),
+            words.clone().filter(|word| filter.accepts(word)).count()
+        );
+        for word in dictionary.words() {
+            let graphemes = word.graphemes(true).count();
+            assert!((2..=10).contains(&graphemes), "{:?}", word);
+            assert!(!word.contains('\''), "{:?}", word);
+            assert!(!word.chars().any(char::is_uppercase), "{:?}", word);
+            assert!(!word.starts_with('z'), "{:?}", word);
+        }
+
+        let acronyms = WordFilter::new().with_case_filter(CaseFilter::RejectAllUppercase);
+        assert!(acronyms.accepts("I"));
+        assert!(acronyms.accepts("Nasa"));
+        assert!(!acronyms.accepts("NASA"));
+        assert!(!acronyms.accepts("U.S."));
+
+        // Only the first spelling of each word is kept, and repeats are still merged.
+        let variants = WordFilter::new().with_case_filter(CaseFilter::RejectCaseVariants);
+        assert!(variants.accepts("Polish"));
+        let dictionary = Dictionary::with_filter(
+            ["Polish", "polish", "POLISH", "Nice", "Nice", "ice"],
+            Settings::new(),
+            variants.clone(),
+        );
+        assert_eq!(
+            dictionary.words().collect::<Vec<_>>(),
+            ["Nice", "Polish", "ice"]
+        );
+        let mut builder = DictionaryBuilder::memory(Settings::new()).with_filter(variants);
+        builder.insert_weighted("Polish", 1).unwrap();
+        builder.insert_weighted("Polish", 2).unwrap();
+        builder.insert_weighted("polish", 4).unwrap();
+        let dictionary = builder.into_dictionary().unwrap();
+        assert_eq!(dictionary.weight("Polish"), Some(3));
+        assert!(!dictionary.contains("polish"));
+
+        let hyphenated = WordFilter::new()
+            .with_allowed_characters(CharacterClasses::ALPHABETIC | CharacterClasses::HYPHENS);
+        assert!(hyphenated.accepts("x-ray"));
+        assert!(!hyphenated.accepts("x ray"));
+        assert!(!hyphenated.accepts("cafe\u{301}"));
+
+        // The filter sees words before they are canonicalized, and runs only once each.
+        let calls = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counted = {
+            let calls = calls.clone();
+            WordFilter::new().with_predicate(move |word| {
+                calls.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                word != "Ice"
+            })
+        };
+        let mut builder =
+            DictionaryBuilder::memory(Settings::new().with_case_folding(CaseFolding::Full))
+                .with_max_run_bytes(4)
+                .with_filter(counted);
+        builder.extend_unsorted(["just", "Ice", "ice", ""]).unwrap();
+        let dictionary = builder.into_dictionary().unwrap();
+        assert_eq!(dictionary.words().collect::<Vec<_>>(), ["ice", "just"]);
+        assert_eq!(calls.load(std::sync::atomic::Ordering::Relaxed), 3);
+    }
+
     #[test]
     fn first_matches_repeated_next_back_test() {
         let word_segmentations = include_str!("../american-english-dictionary.txt")
@@ -142,6 +716,35 @@ mod tests {
         assert_eq!(first, next_back_last);
     }
 
+    #[cfg(feature = "std")]
+    #[test]
+    fn from_path_test() {
+        use std::io::Write;
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(b"\xef\xbb\xbfhello\r\n# comment\r\n\r\n  just\t\r\nice\njustice")
+            .unwrap();
+
+        let dictionary = Dictionary::from_path(file.path(), Settings::new()).unwrap();
+        assert_eq!(
+            dictionary.as_bytes(),
+            Dictionary::new(&["hello", "just", "ice", "justice"]).as_bytes()
+        );
+
+        file.write_all(b"\njust\xffice\n").unwrap();
+        match Dictionary::from_path(file.path(), Settings::new()) {
+            Err(Error::InvalidUtf8 { line: 7, error }) => {
+                assert_eq!(error.valid_up_to(), 4)
+            }
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
+        }
+
+        assert!(matches!(
+            Dictionary::from_path(file.path().with_extension("missing"), Settings::new()),
+            Err(Error::Io(_))
+        ));
+    }
+
     #[test]
     fn from_bytes_verified_test() {
         let first_dictionary = Dictionary::new(&["hello", "just", "ice", "justice"]);
@@ -158,6 +761,93 @@ mod tests {
         );
     }
 
+    #[test]
+    fn fuzzy_test() {
+        let settings = Settings::new().with_case_folding(CaseFolding::Full);
+        let dictionary = Dictionary::with_settings(
+            ["just", "ice", "justice", "jüstice", "notice", "🇨🇦🇫🇷"],
+            settings,
+        );
+        let fuzzy = |word, max_edits| dictionary.fuzzy(word, max_edits).collect::<Vec<_>>();
+
+        assert_eq!(fuzzy("JUSTICE", 0), [("justice".to_string(), 0)]);
+        assert_eq!(
+            fuzzy("jusTIce", 1),
+            [
+                ("justice".to_string(), 0),
+                ("ju\u{308}stice".to_string(), 1)
+            ]
+        );
+        assert_eq!(
+            fuzzy("justic", 2),
+            [
+                ("just".to_string(), 2),
+                ("justice".to_string(), 1),
+                ("ju\u{308}stice".to_string(), 2)
+            ]
+        );
+        assert_eq!(
+            fuzzy("ic", 2),
+            [("ice".to_string(), 1), ("🇨🇦🇫🇷".to_string(), 2)]
+        );
+        assert_eq!(fuzzy("🇨🇦", 1), [("🇨🇦🇫🇷".to_string(), 1)]);
+        assert_eq!(
+            fuzzy("", 3),
+            [("ice".to_string(), 3), ("🇨🇦🇫🇷".to_string(), 2)]
+        );
+        assert!(fuzzy("xyz", 2).is_empty());
+    }
+
+    #[test]
+    fn interop_test() {
+        let words = include_str!("../american-english-dictionary.txt").lines();
+        let weighted = words.clone().zip(1..);
+        let dictionary = Dictionary::with_weights(weighted.clone(), Settings::new());
+        let borrowed = Dictionary::from_bytes(dictionary.as_bytes()).unwrap();
+
+        assert_eq!(
+            borrowed.as_fst().as_fst().as_bytes(),
+            dictionary.clone().into_fst().as_fst().as_bytes()
+        );
+        let restored = Dictionary::from_fst_map(dictionary.clone().into_fst(), Settings::new());
+        assert_eq!(restored.unwrap().as_bytes(), dictionary.as_bytes());
+
+        let set = fst::Set::from_iter(dictionary.words()).unwrap();
+        let unweighted = Dictionary::from_fst_set(set, Settings::new()).unwrap();
+        assert_eq!(unweighted.len(), dictionary.len());
+        assert_eq!(unweighted.weight("justice"), Some(0));
+        assert_eq!(
+            unweighted.word_segmentations("justice").count(),
+            dictionary.word_segmentations("justice").count()
+        );
+
+        let invalid_key = |keys: &[&[u8]], settings| match Dictionary::from_fst_set(
+            fst::Set::from_iter(keys).unwrap(),
+            settings,
+        ) {
+            Err(Error::InvalidKey { key }) => Some(key),
+            _ => None,
+        };
+        assert_eq!(invalid_key(&[b"", b"ice"], Settings::new()), Some(vec![]));
+        assert_eq!(
+            invalid_key(&[b"ice", b"\xff"], Settings::new()),
+            Some(b"\xff".to_vec())
+        );
+        assert_eq!(
+            invalid_key(
+                &[b"Ice", b"ice"],
+                Settings::new().with_case_folding(CaseFolding::Full)
+            ),
+            Some(b"Ice".to_vec())
+        );
+        let nfc = Settings::new().with_normalization_form(NormalizationForm::Nfc);
+        assert_eq!(invalid_key(&["caf\u{e9}".as_bytes()], nfc), None);
+        assert_eq!(
+            invalid_key(&["cafe\u{301}".as_bytes()], nfc),
+            Some("cafe\u{301}".as_bytes().to_vec())
+        );
+    }
+
     #[test]
     fn last_matches_repeated_next_test() {
         let word_segmentations = include_str!("../american-english-dictionary.txt")
@@ -174,6 +864,176 @@ mod tests {
         assert_eq!(last, next_last);
     }
 
+    #[test]
+    fn normalization_form_test() {
+        let settings = Settings::new().with_normalization_form(NormalizationForm::Nfkc);
+        let dictionary = Dictionary::with_settings(["file", "box", "café"], settings);
+
+        assert_eq!(
+            dictionary
+                .word_segmentations("ﬁle\u{ff42}\u{ff4f}\u{ff58}")
+                .collect::<Vec<_>>(),
+            [vec!["ﬁle", "\u{ff42}\u{ff4f}\u{ff58}"]]
+        );
+        assert_eq!(
+            dictionary
+                .word_segmentations("cafe\u{301}")
+                .collect::<Vec<_>>(),
+            [vec!["cafe\u{301}"]]
+        );
+
+        let dictionary = Dictionary::from_bytes(dictionary.as_bytes()).unwrap();
+        assert_eq!(dictionary.settings(), settings);
+        assert_eq!(dictionary.word_segmentations("ﬁle").count(), 1);
+
+        let dictionary = Dictionary::new(&["file"]);
+        assert_eq!(
+            dictionary.settings().normalization_form(),
+            NormalizationForm::Nfd
+        );
+        assert_eq!(dictionary.word_segmentations("ﬁle").count(), 0);
+    }
+
+    #[test]
+    fn layered_dictionary_test() {
+        let settings = Settings::new().with_case_folding(CaseFolding::Full);
+        let base = Dictionary::with_payloads(
+            [("just", 1), ("ice", 2), ("justice", 3), ("cream", 4)],
+            settings,
+        );
+        let mut dictionary = LayeredDictionary::new(base);
+        dictionary
+            .push_removals(Dictionary::with_settings(["ICE", "cream"], settings))
+            .unwrap();
+        dictionary
+            .push_additions(Dictionary::with_payloads(
+                [("Ice", 20), ("i", 30)],
+                settings,
+            ))
+            .unwrap();
+
+        assert!(dictionary.contains("JUST"));
+        assert!(dictionary.contains("ice"));
+        assert!(!dictionary.contains("cream"));
+        assert!(!dictionary.contains(""));
+        assert_eq!(dictionary.layers().count(), 3);
+
+        let mut word_segmentations = dictionary
+            .word_segmentations_with_payloads("JustIce")
+            .collect::<Vec<_>>();
+        word_segmentations.sort_unstable();
+        assert_eq!(
+            word_segmentations,
+            [vec![("Just", 1), ("Ice", 20)], vec![("JustIce", 3)]]
+        );
+        assert_eq!(dictionary.word_segmentations("justicecream").count(), 0);
+        assert_eq!(
+            dictionary.word_segmentations("justi").collect::<Vec<_>>(),
+            [vec!["just", "i"]]
+        );
+
+        let payload_settings = settings.with_value_kind(ValueKind::Payloads);
+        let other = Dictionary::new(&["just"]);
+        assert_eq!(
+            dictionary.push_additions(other),
+            Err(SettingsMismatch {
+                left: payload_settings,
+                right: Settings::new()
+            })
+        );
+        let weighted = Dictionary::with_settings(["just"], settings);
+        assert_eq!(
+            dictionary.push_additions(weighted),
+            Err(SettingsMismatch {
+                left: payload_settings,
+                right: settings
+            })
+        );
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn mmap_test() {
+        use std::io::Write;
+
+        let dictionary = Dictionary::with_weights([("just", 1), ("ice", 2)], Settings::new());
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(dictionary.as_bytes()).unwrap();
+
+        let mapped = unsafe { Dictionary::open_mmap_verified(file.path()) }.unwrap();
+        assert_eq!(mapped.as_bytes(), dictionary.as_bytes());
+        assert_eq!(mapped.weight("ice"), Some(2));
+        let word_segmentations = mapped.word_segmentations("justice");
+        drop(mapped);
+        assert_eq!(
+            word_segmentations.collect::<Vec<_>>(),
+            [vec!["just", "ice"]]
+        );
+
+        let mut corrupt_file = tempfile::NamedTempFile::new().unwrap();
+        corrupt_file.write_all(b"not a dictionary").unwrap();
+        assert!(matches!(
+            unsafe { Dictionary::open_mmap(corrupt_file.path()) },
+            Err(Error::NotADictionary)
+        ));
+        assert!(matches!(
+            unsafe { Dictionary::open_mmap(file.path().with_extension("missing")) },
+            Err(Error::Io(_))
+        ));
+    }
+
+    #[test]
+    fn mutable_dictionary_test() {
+        let settings = Settings::new().with_case_folding(CaseFolding::Full);
+        let base = Dictionary::with_weights([("just", 1), ("ice", 2), ("justice", 3)], settings);
+        let mut dictionary = MutableDictionary::new(base);
+
+        assert!(!dictionary.insert("ICE"));
+        assert!(dictionary.insert_weighted("Cream", 4));
+        assert!(!dictionary.insert("cream"));
+        assert!(dictionary.remove("Justice"));
+        assert!(!dictionary.remove("justice"));
+        assert!(!dictionary.remove("hello"));
+        assert!(!dictionary.insert(""));
+        assert_eq!(dictionary.pending_edits(), 2);
+        assert!(dictionary.contains("CREAM"));
+        assert!(!dictionary.contains("justice"));
+        assert_eq!(
+            dictionary
+                .word_segmentations_with_payloads("JusticeCream")
+                .collect::<Vec<_>>(),
+            [vec![("Just", 1), ("ice", 2), ("Cream", 4)]]
+        );
+
+        assert!(dictionary.insert("justice"));
+        assert!(dictionary.remove("cream"));
+        assert_eq!(dictionary.pending_edits(), 0);
+        assert_eq!(dictionary.word_segmentations("justicecream").count(), 0);
+
+        dictionary.insert("cream");
+        dictionary.remove("ice");
+        let compaction = dictionary.start_compaction();
+        dictionary.insert("ice");
+        dictionary.remove("cream");
+        dictionary.insert("hello");
+        let compacted = compaction.run();
+        let stale = dictionary.start_compaction().run();
+        assert!(dictionary.finish_compaction(compacted));
+        assert!(!dictionary.finish_compaction(stale));
+        assert!(dictionary.base().words().eq(["cream", "just", "justice"]));
+        assert_eq!(dictionary.pending_edits(), 3);
+        assert_eq!(dictionary.base().weight("just"), Some(1));
+
+        dictionary.compact();
+        assert_eq!(dictionary.pending_edits(), 0);
+        assert!(dictionary
+            .base()
+            .words()
+            .eq(["hello", "ice", "just", "justice"]));
+        assert_eq!(dictionary.base().weight("ice"), Some(2));
+        assert_eq!(dictionary.word_segmentations("justice").count(), 2);
+    }
+
     #[test]
     fn no_matching_word_segmentations_test() {
         let dictionary = Dictionary::new(&["b"]);
@@ -235,6 +1095,272 @@ mod tests {
         }
     }
 
+    #[test]
+    fn payloads_test() {
+        let settings = Settings::new().with_case_folding(CaseFolding::Full);
+        let dictionary = Dictionary::with_payloads(
+            [
+                ("Just", 1),
+                ("ice", 2),
+                ("justice", 4),
+                ("JUSTICE", 4),
+                ("café", 5),
+            ],
+            settings,
+        );
+        let dictionary = Dictionary::from_bytes(dictionary.as_bytes().to_vec()).unwrap();
+
+        assert_eq!(dictionary.settings().value_kind(), ValueKind::Payloads);
+        assert_eq!(dictionary.payload("justice"), Some(4));
+        assert_eq!(dictionary.payload("cafe\u{301}"), Some(5));
+
+        let input = "JustIceCafé";
+        let segmentations = dictionary
+            .word_segmentations_with_payloads(input)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            segmentations,
+            [
+                vec![("Just", 1), ("Ice", 2), ("Café", 5)],
+                vec![("JustIce", 4), ("Café", 5)]
+            ]
+        );
+        assert_eq!(
+            dictionary
+                .word_segmentations_with_payloads("justice")
+                .rev()
+                .map(|segmentation| segmentation.into_iter().map(|(word, _)| word).collect())
+                .collect::<Vec<Vec<_>>>(),
+            dictionary
+                .word_segmentations("justice")
+                .rev()
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            dictionary.word_segmentations_with_payloads("").next(),
+            Some(vec![])
+        );
+        assert_eq!(
+            dictionary.word_segmentations_with_payloads("x").next(),
+            None
+        );
+
+        assert!(matches!(
+            Dictionary::try_with_payloads([("justice", 3), ("JUSTICE", 4)], settings),
+            Err(Error::ConflictingPayloads { word, first: 3, second: 4 }) if word == "justice"
+        ));
+        let mut builder = DictionaryBuilder::memory(settings.with_value_kind(ValueKind::Payloads));
+        builder.insert_weighted("ice", 2).unwrap();
+        assert!(matches!(
+            builder.insert_weighted("ice", 1),
+            Err(Error::ConflictingPayloads {
+                first: 2,
+                second: 1,
+                ..
+            })
+        ));
+        let mut builder = DictionaryBuilder::memory(settings.with_value_kind(ValueKind::Payloads))
+            .with_max_run_bytes(4);
+        assert!(matches!(
+            builder.extend_unsorted_weighted([("just", 1), ("ice", 2), ("cream", 3), ("ice", 4)]),
+            Err(Error::ConflictingPayloads {
+                first: 2,
+                second: 4,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn range_test() {
+        let dictionary = Dictionary::new(&["ab", "abc", "cd", "def", "abcd", "ef", "c"]);
+
+        assert_eq!(
+            dictionary.range("abc".."d").collect::<Vec<_>>(),
+            ["abc", "abcd", "c", "cd"]
+        );
+        assert_eq!(
+            dictionary.range("abc"..="def").collect::<Vec<_>>(),
+            ["abc", "abcd", "c", "cd", "def"]
+        );
+        assert_eq!(dictionary.range(.."abc").collect::<Vec<_>>(), ["ab"]);
+        assert!(dictionary.range::<_, &str>(..).eq(dictionary.words()));
+    }
+
+    #[test]
+    fn search_test() {
+        let dictionary = Dictionary::new(&["cat", "cats", "coat", "cot", "café", "dog", "a*b"]);
+        let search = |pattern: Pattern| dictionary.search(&pattern).collect::<Vec<_>>();
+
+        assert_eq!(search(Pattern::glob("c?t").unwrap()), ["cat", "cot"]);
+        assert_eq!(search(Pattern::glob("*t").unwrap()), ["cat", "coat", "cot"]);
+        assert_eq!(
+            search(Pattern::glob("ca[a-z]?").unwrap()),
+            ["cafe\u{301}", "cats"]
+        );
+        assert_eq!(search(Pattern::glob("caf?").unwrap()), ["cafe\u{301}"]);
+        assert_eq!(search(Pattern::glob("caf[é]").unwrap()), ["cafe\u{301}"]);
+        assert_eq!(search(Pattern::glob("a\\*b").unwrap()), ["a*b"]);
+        assert_eq!(search(Pattern::glob("[!c]*").unwrap()), ["a*b", "dog"]);
+
+        assert_eq!(search(Pattern::regex("co*t").unwrap()), ["cot"]);
+        assert_eq!(
+            search(Pattern::regex("c(o|a)+ts?").unwrap()),
+            ["cat", "cats", "coat", "cot"]
+        );
+        assert_eq!(
+            search(Pattern::regex("^(dog|cat)$").unwrap()),
+            ["cat", "dog"]
+        );
+        assert_eq!(search(Pattern::regex("café?").unwrap()), ["cafe\u{301}"]);
+        assert_eq!(search(Pattern::regex("c[^a]..").unwrap()), ["coat"]);
+
+        // Ranges compare whole graphemes, however they are normalized.
+        assert_eq!(search(Pattern::glob("caf[à-ÿ]").unwrap()), ["cafe\u{301}"]);
+        assert_eq!(
+            search(Pattern::glob("caf[a-z]").unwrap()),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            search(Pattern::regex("caf[^a-z]").unwrap()),
+            ["cafe\u{301}"]
+        );
+        assert_eq!(
+            search(Pattern::regex("caf[e\u{300}-e\u{301}]").unwrap()),
+            ["cafe\u{301}"]
+        );
+
+        let dictionary = include_str!("../american-english-dictionary.txt")
+            .lines()
+            .collect::<Dictionary<_>>();
+        assert!(dictionary
+            .search(&Pattern::glob("*ing").unwrap())
+            .eq(dictionary.words().filter(|word| word.ends_with("ing"))));
+        assert!(dictionary
+            .search(&Pattern::regex("jus.*").unwrap())
+            .eq(dictionary.words_with_prefix("jus", None)));
+
+        assert_eq!(
+            Pattern::glob("ab\\").unwrap_err(),
+            PatternError::TrailingBackslash { position: 2 }
+        );
+        assert_eq!(
+            Pattern::glob("a[bc").unwrap_err(),
+            PatternError::UnclosedClass { position: 1 }
+        );
+        assert_eq!(
+            Pattern::regex("[z-a]").unwrap_err(),
+            PatternError::InvalidRange { position: 1 }
+        );
+        assert_eq!(
+            Pattern::regex("a(b|c").unwrap_err(),
+            PatternError::UnclosedGroup { position: 1 }
+        );
+        assert_eq!(
+            Pattern::regex("ab)").unwrap_err(),
+            PatternError::UnopenedGroup { position: 2 }
+        );
+        assert_eq!(
+            Pattern::regex("*a").unwrap_err(),
+            PatternError::NothingToRepeat { position: 0 }
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_test() {
+        let settings = Settings::new()
+            .with_case_folding(CaseFolding::Turkic)
+            .with_normalization_form(NormalizationForm::Nfkc);
+        let dictionary = Dictionary::with_payloads([("just", 1), ("ice", 2)], settings);
+
+        let json = serde_json::to_string(&dictionary).unwrap();
+        let deserialized: Dictionary<Vec<u8>> = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.as_bytes(), dictionary.as_bytes());
+        assert_eq!(
+            deserialized.settings(),
+            settings.with_value_kind(ValueKind::Payloads)
+        );
+
+        let settings_json = serde_json::to_string(&settings).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Settings>(&settings_json).unwrap(),
+            settings
+        );
+
+        let mut corrupt = dictionary.as_bytes().to_vec();
+        *corrupt.last_mut().unwrap() ^= 1;
+        let json = serde_json::to_string(&serde_json::Value::from(corrupt)).unwrap();
+        match serde_json::from_str::<Dictionary<Vec<u8>>>(&json) {
+            Err(e) => assert!(e.to_string().contains("fingerprint")),
+            Ok(_) => panic!("deserialized a corrupt dictionary"),
+        }
+
+        let mut word_segmentations = dictionary.word_segmentations_with_payloads("JustIce");
+        assert_eq!(serde_json::to_string(&word_segmentations).unwrap(), "[]");
+        word_segmentations = dictionary.word_segmentations_with_payloads("justice");
+        assert_eq!(
+            serde_json::to_string(&word_segmentations).unwrap(),
+            r#"[[["just",1],["ice",2]]]"#
+        );
+        assert_eq!(word_segmentations.count(), 1);
+    }
+
+    #[test]
+    fn set_operations_test() {
+        let first = Dictionary::new(&["ab", "abc", "cd", "def"]);
+        let second = Dictionary::new(&["abc", "def", "abcd", "ef", "c"]);
+
+        assert_eq!(
+            first.union(&second).unwrap().as_bytes(),
+            Dictionary::new(&["ab", "abc", "cd", "def", "abcd", "ef", "c"]).as_bytes()
+        );
+        assert_eq!(
+            first.intersection(&second).unwrap().as_bytes(),
+            Dictionary::new(&["abc", "def"]).as_bytes()
+        );
+        assert_eq!(
+            first.difference(&second).unwrap().as_bytes(),
+            Dictionary::new(&["ab", "cd"]).as_bytes()
+        );
+        assert_eq!(
+            first.symmetric_difference(&second).unwrap().as_bytes(),
+            Dictionary::new(&["ab", "cd", "abcd", "ef", "c"]).as_bytes()
+        );
+        assert_eq!(
+            first.difference(&first).unwrap().as_bytes(),
+            Dictionary::new::<&str>(&[]).as_bytes()
+        );
+
+        let folded =
+            Dictionary::with_settings(["ab"], Settings::new().with_case_folding(CaseFolding::Full));
+        assert_eq!(
+            first.union(&folded).map(|_| ()),
+            Err(SettingsMismatch {
+                left: first.settings(),
+                right: folded.settings(),
+            })
+        );
+
+        let first = Dictionary::with_payloads([("ab", 1), ("abc", 2)], Settings::new());
+        let second = Dictionary::with_payloads([("abc", 3), ("c", 4)], Settings::new());
+        let union = first.union(&second).unwrap();
+        assert_eq!(union.settings(), first.settings());
+        assert_eq!(union.payload("abc"), Some(2));
+        assert_eq!(union.payload("c"), Some(4));
+        assert_eq!(second.union(&first).unwrap().payload("abc"), Some(3));
+        assert_eq!(first.intersection(&second).unwrap().payload("abc"), Some(2));
+
+        let weighted = Dictionary::with_weights([("abc", 3)], Settings::new());
+        assert_eq!(
+            first.union(&weighted).map(|_| ()),
+            Err(SettingsMismatch {
+                left: first.settings(),
+                right: weighted.settings(),
+            })
+        );
+    }
+
     #[test]
     fn size_hint_test() {
         let word_segmentations = include_str!("../american-english-dictionary.txt")
@@ -258,4 +1384,239 @@ mod tests {
         assert_eq!(forward_iter.next(), None);
         assert_eq!(reverse_iter.next_back(), None);
     }
+
+    #[test]
+    fn stats_test() {
+        let stats = Dictionary::new(&[] as &[&str]).stats();
+        assert_eq!(stats.word_count, 0);
+        assert_eq!(stats.byte_lengths, Lengths::default());
+        assert_eq!(stats.fst_node_count, 1);
+
+        let settings = Settings::new().with_case_folding(CaseFolding::Full);
+        let dictionary =
+            Dictionary::with_settings(["A", "ab", "AB", "abc", "ﬁ", "x\u{301}"], settings);
+        let stats = dictionary.stats();
+        assert_eq!(stats.word_count, 5);
+        assert_eq!(
+            stats.byte_lengths,
+            Lengths {
+                min: 1,
+                max: 3,
+                mean: 2.2
+            }
+        );
+        assert_eq!(
+            stats.grapheme_lengths,
+            Lengths {
+                min: 1,
+                max: 3,
+                mean: 1.8
+            }
+        );
+        assert_eq!(
+            stats.distinct_graphemes.iter().collect::<Vec<_>>(),
+            ["a", "b", "c", "f", "i", "x\u{301}"]
+        );
+        assert_eq!(stats.single_grapheme_word_count, 2);
+        assert_eq!(
+            stats.fst_byte_count,
+            dictionary.as_bytes().len() - (format::HEADER_LEN + format::FOOTER_LEN)
+        );
+        assert!(stats.fst_node_count > 1);
+
+        let dictionary = include_str!("../american-english-dictionary.txt")
+            .lines()
+            .collect::<Dictionary<_>>();
+        let stats = dictionary.stats();
+        assert_eq!(stats.word_count, dictionary.len());
+        assert!(stats.fst_node_count > stats.distinct_graphemes.len());
+    }
+
+    #[test]
+    fn turkic_case_folding_test() {
+        let settings = Settings::new().with_case_folding(CaseFolding::Turkic);
+        let dictionary = Dictionary::with_settings(["ılık", "istanbul"], settings);
+
+        assert_eq!(
+            dictionary
+                .word_segmentations("ILIKİSTANBUL")
+                .collect::<Vec<_>>(),
+            [vec!["ILIK", "İSTANBUL"]]
+        );
+        assert_eq!(dictionary.word_segmentations("ISTANBUL").count(), 0);
+
+        let settings = Settings::new().with_case_folding(CaseFolding::Full);
+        let dictionary = Dictionary::with_settings(["ılık", "istanbul"], settings);
+
+        assert_eq!(dictionary.word_segmentations("ILIK").count(), 0);
+        assert_eq!(dictionary.word_segmentations("ISTANBUL").count(), 1);
+    }
+
+    #[test]
+    fn weight_test() {
+        let dictionary = Dictionary::with_weights(
+            [
+                ("café", 3),
+                ("cafe\u{301}", 4),
+                ("just", 10),
+                ("ice", u64::MAX),
+            ],
+            Settings::new(),
+        );
+        let dictionary = Dictionary::from_bytes(dictionary.as_bytes().to_vec()).unwrap();
+
+        assert_eq!(dictionary.weight("café"), Some(7));
+        assert_eq!(dictionary.weight("just"), Some(10));
+        assert_eq!(dictionary.weight("justice"), None);
+        assert_eq!(dictionary.weight(""), None);
+
+        let other = Dictionary::with_weights([("ice", 1), ("cream", 2)], Settings::new());
+        let union = dictionary.union(&other).unwrap();
+        assert_eq!(union.weight("ice"), Some(u64::MAX));
+        assert_eq!(union.weight("cream"), Some(2));
+        assert_eq!(Dictionary::new(&["just"]).weight("just"), Some(0));
+
+        let mut builder = DictionaryBuilder::memory(Settings::new()).with_max_run_bytes(8);
+        builder
+            .extend_unsorted_weighted([("ice", 1), ("just", 2), ("ice", 3), ("just", 4)])
+            .unwrap();
+        let dictionary = builder.into_dictionary().unwrap();
+        assert_eq!(dictionary.weight("ice"), Some(4));
+        assert_eq!(dictionary.weight("just"), Some(6));
+
+        // The values of these constructors are never payloads that could conflict.
+        let payload_settings = Settings::new().with_value_kind(ValueKind::Payloads);
+        let dictionary = Dictionary::with_weights([("ice", 1), ("ice", 2)], payload_settings);
+        assert_eq!(dictionary.weight("ice"), Some(3));
+        assert_eq!(dictionary.settings().value_kind(), ValueKind::Weights);
+        assert!(Dictionary::try_with_weights([("ice", 1), ("ice", 2)], payload_settings).is_ok());
+        assert_eq!(
+            Dictionary::with_settings(["ice", "ice"], payload_settings).weight("ice"),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn word_matcher_test() {
+        use alloc::collections::BTreeSet;
+        #[cfg(feature = "std")]
+        use std::collections::HashSet;
+
+        let dictionary = include_str!("../american-english-dictionary.txt")
+            .lines()
+            .collect::<Dictionary<_>>();
+        let btree_set = dictionary.words().collect::<BTreeSet<_>>();
+        let btree_set = WordSet::new(&btree_set, Settings::new()).unwrap();
+        #[cfg(feature = "std")]
+        let hash_set = dictionary.words().collect::<HashSet<_>>();
+        #[cfg(feature = "std")]
+        let hash_set = WordSet::new(&hash_set, Settings::new()).unwrap();
+
+        for input in [
+            "thisisatest",
+            "justiceforall",
+            "cafe\u{301}aulait",
+            "café",
+            "zzz",
+            "",
+        ] {
+            let mut expected = dictionary.word_segmentations(input).collect::<Vec<_>>();
+            expected.sort_unstable();
+
+            let mut from_dictionary =
+                WordSegmentations::from_matcher(&dictionary, input).collect::<Vec<_>>();
+            from_dictionary.sort_unstable();
+            assert_eq!(from_dictionary, expected, "{:?}", input);
+
+            let mut from_btree_set =
+                WordSegmentations::from_matcher(btree_set, input).collect::<Vec<_>>();
+            from_btree_set.sort_unstable();
+            assert_eq!(from_btree_set, expected, "{:?}", input);
+            assert_eq!(
+                WordSegmentations::from_matcher(btree_set, input).count(),
+                expected.len()
+            );
+
+            #[cfg(feature = "std")]
+            {
+                let mut from_hash_set =
+                    WordSegmentations::from_matcher(hash_set, input).collect::<Vec<_>>();
+                from_hash_set.sort_unstable();
+                assert_eq!(from_hash_set, expected, "{:?}", input);
+                assert!(WordSegmentationsWithPayloads::from_matcher(hash_set, input)
+                    .flatten()
+                    .all(|(_, payload)| payload == 0));
+            }
+        }
+
+        // Words that aren't canonical according to the settings are rejected.
+        let nfc = core::iter::once("caf\u{e9}".to_string()).collect::<BTreeSet<_>>();
+        assert!(matches!(
+            WordSet::new(&nfc, Settings::new()),
+            Err(Error::InvalidKey { .. })
+        ));
+        let empty = core::iter::once(String::new()).collect::<BTreeSet<_>>();
+        assert!(WordSet::new(&empty, Settings::new()).is_err());
+
+        let settings = Settings::new().with_case_folding(CaseFolding::Full);
+        let folded = ["just", "ice"]
+            .iter()
+            .map(|word| settings.canonicalize(word))
+            .collect::<BTreeSet<_>>();
+        assert!(WordSet::new(&folded, settings).is_ok());
+        let unfolded = ["Just", "ice"]
+            .iter()
+            .map(|word| word.to_string())
+            .collect::<BTreeSet<_>>();
+        assert!(WordSet::new(&unfolded, settings).is_err());
+        assert_eq!(
+            WordSegmentations::from_matcher(WordSet::new(&folded, settings).unwrap(), "JUSTice")
+                .collect::<Vec<_>>(),
+            [vec!["JUST", "ice"]]
+        );
+    }
+
+    #[test]
+    fn words_test() {
+        let words = include_str!("../american-english-dictionary.txt").lines();
+        let dictionary = words.clone().collect::<Dictionary<_>>();
+        let bytes = dictionary.as_bytes().to_vec();
+        let dictionary = Dictionary::from_bytes(&bytes[..]).unwrap();
+
+        assert_eq!(dictionary.len(), dictionary.words().count());
+        assert_eq!(
+            dictionary.words().collect::<Dictionary<_>>().as_bytes(),
+            &bytes[..]
+        );
+    }
+
+    #[test]
+    fn words_with_prefix_test() {
+        let settings = Settings::new().with_case_folding(CaseFolding::Full);
+        let dictionary = Dictionary::with_settings(
+            ["e", "é", "éa", "ea", "eb", "f", "e\u{301}\u{302}"],
+            settings,
+        );
+
+        assert_eq!(
+            dictionary.words_with_prefix("E", None).collect::<Vec<_>>(),
+            ["e", "ea", "eb"]
+        );
+        assert_eq!(
+            dictionary.words_with_prefix("É", None).collect::<Vec<_>>(),
+            ["e\u{301}", "e\u{301}a"]
+        );
+        assert_eq!(
+            dictionary
+                .words_with_prefix("e", Some(2))
+                .collect::<Vec<_>>(),
+            ["e", "ea"]
+        );
+        assert_eq!(dictionary.words_with_prefix("e", Some(0)).count(), 0);
+        assert_eq!(
+            dictionary.words_with_prefix("", None).collect::<Vec<_>>(),
+            dictionary.words().collect::<Vec<_>>()
+        );
+        assert_eq!(dictionary.words_with_prefix("g", None).count(), 0);
+    }
 }
@@ -0,0 +1,243 @@
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter};
+use std::path::{Component, Path, PathBuf};
+
+use crate::{Dictionary, DictionaryBuilder, Error, Settings};
+
+impl Dictionary<Vec<u8>> {
+    /// Creates a new
+    /// <code>[Dictionary](crate::Dictionary)&lt;[Vec](alloc::vec::Vec)&lt;[u8](core::primitive::u8)&gt;&gt;</code>
+    /// from a word list with one word per line, canonicalizing the words according to
+    /// the given <code>[Settings](crate::Settings)</code>.
+    ///
+    /// A UTF-8 byte order mark at the start is skipped, both LF and CRLF line endings
+    /// are accepted, whitespace around each word is trimmed, and blank lines and lines
+    /// starting with <code>#</code> are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::{Dictionary, Settings};
+    ///
+    /// let word_list = "\u{feff}# greetings\r\nhello\r\n  just \r\nice\r\njustice\r\n";
+    /// let dictionary = Dictionary::from_reader(word_list.as_bytes(), Settings::new()).unwrap();
+    /// let mut word_segmentations = dictionary
+    ///     .word_segmentations("justice")
+    ///     .collect::<Vec<_>>();
+    ///
+    /// word_segmentations.sort_unstable();
+    /// assert_eq!(word_segmentations, [vec!["just", "ice"], vec!["justice"]]);
+    /// ```
+    pub fn from_reader<R>(reader: R, settings: Settings) -> Result<Self, Error>
+    where
+        R: BufRead,
+    {
+        let mut error = None;
+        let mut builder = DictionaryBuilder::memory(settings);
+        builder.extend_unsorted(read_words(reader, &mut error))?;
+
+        match error {
+            Some(error) => Err(error),
+            None => Ok(builder.into_dictionary()?),
+        }
+    }
+
+    /// Creates a new
+    /// <code>[Dictionary](crate::Dictionary)&lt;[Vec](alloc::vec::Vec)&lt;[u8](core::primitive::u8)&gt;&gt;</code>
+    /// from the word list in the file at <code>path</code>, as with
+    /// <code>[Dictionary](crate::Dictionary)::[from_reader](crate::Dictionary::from_reader)</code>.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::{Dictionary, Settings};
+    ///
+    /// let dictionary =
+    ///     Dictionary::from_path("american-english-dictionary.txt", Settings::new()).unwrap();
+    ///
+    /// assert_eq!(
+    ///     dictionary
+    ///         .word_segmentations("thequickbrownfoxjumpsoverthelazydog")
+    ///         .nth(71257),
+    ///     Some(vec!["the", "quick", "brown", "fox", "jumps", "over", "the", "lazy", "dog"])
+    /// );
+    /// ```
+    pub fn from_path<P>(path: P, settings: Settings) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        Self::from_reader(BufReader::new(File::open(path)?), settings)
+    }
+}
+
+/// Compiles the word list at <code>word_list</code>, a path relative to the package
+/// root, into the bytes of a <code>[Dictionary](crate::Dictionary)</code> in the
+/// <code>OUT_DIR</code> of a build script, for
+/// <code>[include_dictionary!](crate::include_dictionary)</code> to embed.
+///
+/// The word list is read as with
+/// <code>[Dictionary](crate::Dictionary)::[from_reader](crate::Dictionary::from_reader)</code>,
+/// and its words are streamed to the file as they are sorted, so even a large word
+/// list is compiled without holding the whole
+/// <code>[Dictionary](crate::Dictionary)</code> in memory.
+///
+/// # Examples
+///
+/// In <code>build.rs</code>, with <code>wordbreaker</code> also listed under
+/// <code>[build-dependencies]</code>:
+///
+/// ```rust,no_run
+/// use wordbreaker::Settings;
+///
+/// fn main() {
+///     println!("cargo:rerun-if-changed=words.txt");
+///     wordbreaker::compile_word_list("words.txt", Settings::new()).unwrap();
+/// }
+/// ```
+pub fn compile_word_list<P>(word_list: P, settings: Settings) -> Result<(), Error>
+where
+    P: AsRef<Path>,
+{
+    let out_dir = std::env::var_os("OUT_DIR").ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "OUT_DIR is not set, so this is not running in a build script",
+        )
+    })?;
+    compile_word_list_to(out_dir, word_list, settings)
+}
+
+/// Compiles the word list at <code>word_list</code> into the bytes of a
+/// <code>[Dictionary](crate::Dictionary)</code> in <code>out_dir</code>, as with
+/// <code>[compile_word_list](crate::compile_word_list)</code>.
+///
+/// The bytes are written to <code>word_list</code> with <code>.dict</code> appended,
+/// under <code>out_dir</code>, so <code>word_list</code> must be a relative path
+/// without any <code>..</code>, or this returns an
+/// <code>[Error::Io](crate::Error::Io)</code> of kind
+/// <code>[InvalidInput](std::io::ErrorKind::InvalidInput)</code>. If compiling fails
+/// partway, the partly written file is removed.
+///
+/// # Examples
+///
+/// ```rust
+/// use wordbreaker::{Dictionary, Settings};
+///
+/// let out_dir = tempfile::tempdir().unwrap();
+/// wordbreaker::compile_word_list_to(
+///     out_dir.path(),
+///     "american-english-dictionary.txt",
+///     Settings::new(),
+/// )
+/// .unwrap();
+///
+/// let bytes = std::fs::read(out_dir.path().join("american-english-dictionary.txt.dict")).unwrap();
+/// let dictionary = Dictionary::from_bytes_verified(&bytes[..]).unwrap();
+///
+/// assert!(dictionary.contains("justice"));
+/// ```
+pub fn compile_word_list_to<O, P>(out_dir: O, word_list: P, settings: Settings) -> Result<(), Error>
+where
+    O: AsRef<Path>,
+    P: AsRef<Path>,
+{
+    let word_list = word_list.as_ref();
+    let escapes = word_list
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_) | Component::CurDir));
+    if escapes || word_list.file_name().is_none() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the word list must be a relative path without `..`",
+        )
+        .into());
+    }
+
+    let mut output = out_dir.as_ref().join(word_list).into_os_string();
+    output.push(".dict");
+    let output = PathBuf::from(output);
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let words = BufReader::new(File::open(word_list)?);
+    let file = File::create(&output)?;
+    let result = write_word_list(words, BufWriter::new(file), settings);
+    if result.is_err() {
+        // Don't leave a partial dictionary for include_dictionary! to embed.
+        let _ = std::fs::remove_file(&output);
+    }
+
+    result
+}
+
+/// Writes the bytes of a <code>[Dictionary](crate::Dictionary)</code> with the words
+/// of the word list read by <code>reader</code> to <code>writer</code>.
+fn write_word_list<R, W>(reader: R, writer: BufWriter<W>, settings: Settings) -> Result<(), Error>
+where
+    R: BufRead,
+    W: io::Write,
+{
+    let mut error = None;
+    let mut builder = DictionaryBuilder::new(writer, settings)?;
+    builder.extend_unsorted(read_words(reader, &mut error))?;
+    if let Some(error) = error {
+        return Err(error);
+    }
+
+    builder
+        .finish()?
+        .into_inner()
+        .map_err(io::IntoInnerError::into_error)?;
+    Ok(())
+}
+
+/// Returns an <code>[Iterator](core::iter::Iterator)</code> over the words of a word
+/// list with one word per line, as described for
+/// <code>[Dictionary](crate::Dictionary)::[from_reader](crate::Dictionary::from_reader)</code>.
+///
+/// It stops at the first error, which it stores in <code>error</code>.
+fn read_words<'e, R>(
+    mut reader: R,
+    error: &'e mut Option<Error>,
+) -> impl Iterator<Item = String> + 'e
+where
+    R: BufRead + 'e,
+{
+    let mut line = Vec::new();
+    let mut line_number = 0_usize;
+
+    core::iter::from_fn(move || loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) => return None,
+            Ok(_) => line_number = line_number.wrapping_add(1),
+            Err(e) => {
+                *error = Some(Error::Io(e));
+                return None;
+            }
+        }
+
+        let mut bytes = line.as_slice();
+        if line_number == 1 {
+            bytes = bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(bytes);
+        }
+        let word = match core::str::from_utf8(bytes) {
+            Ok(word) => word.trim(),
+            Err(e) => {
+                *error = Some(Error::InvalidUtf8 {
+                    line: line_number,
+                    error: e,
+                });
+                return None;
+            }
+        };
+
+        if !word.is_empty() && !word.starts_with('#') {
+            return Some(word.to_owned());
+        }
+    })
+}
@@ -0,0 +1,320 @@
+use alloc::collections::BTreeSet;
+use alloc::string::String;
+use core::ops::Bound;
+use fst::raw::{Node, Output};
+
+use crate::{Dictionary, Error, Settings};
+
+/// A set of words that <code>[WordSegmentations](crate::WordSegmentations)</code> can
+/// search an input for, one canonicalized grapheme at a time, so that inputs can be
+/// segmented without first compiling the words into a
+/// <code>[Dictionary](crate::Dictionary)</code>.
+///
+/// A search starts at every position of the input where a word could start. It calls
+/// <code>[start](crate::WordMatcher::start)</code>, then
+/// <code>[advance](crate::WordMatcher::advance)</code> once for each following
+/// grapheme, checking <code>[word_end](crate::WordMatcher::word_end)</code> after each
+/// one, until <code>[advance](crate::WordMatcher::advance)</code> returns
+/// <code>false</code> or the input ends.
+///
+/// This is implemented for references to <code>[Dictionary](crate::Dictionary)</code>,
+/// <code>[LayeredDictionary](crate::LayeredDictionary)</code> and
+/// <code>[MutableDictionary](crate::MutableDictionary)</code>, for sets of words
+/// wrapped in a <code>[WordSet](crate::WordSet)</code>, and can be implemented for
+/// other structures, such as a trie or a table in a database.
+///
+/// # Examples
+///
+/// ```rust
+/// use wordbreaker::{Settings, WordMatcher, WordSegmentationsWithPayloads};
+///
+/// /// Matches every run of ASCII digits, with its length as its payload.
+/// struct Digits;
+///
+/// impl WordMatcher for Digits {
+///     type State = u64;
+///
+///     fn settings(&self) -> Settings {
+///         Settings::new()
+///     }
+///
+///     fn start(&self) -> u64 {
+///         0
+///     }
+///
+///     fn advance(&self, digits: &mut u64, grapheme: &[u8]) -> bool {
+///         *digits += 1;
+///         grapheme.iter().all(u8::is_ascii_digit)
+///     }
+///
+///     fn word_end(&self, digits: &u64) -> Option<u64> {
+///         Some(*digits)
+///     }
+/// }
+///
+/// let mut word_segmentations =
+///     WordSegmentationsWithPayloads::from_matcher(Digits, "123").collect::<Vec<_>>();
+///
+/// word_segmentations.sort_unstable();
+/// assert_eq!(
+///     word_segmentations,
+///     [
+///         vec![("1", 1), ("2", 1), ("3", 1)],
+///         vec![("1", 1), ("23", 2)],
+///         vec![("12", 2), ("3", 1)],
+///         vec![("123", 3)]
+///     ]
+/// );
+/// ```
+pub trait WordMatcher {
+    /// Where a search is in the words, after some graphemes have been matched.
+    type State;
+
+    /// Returns the <code>[Settings](crate::Settings)</code> that inputs are
+    /// canonicalized with, which the words must already be canonical according to.
+    fn settings(&self) -> Settings;
+
+    /// Returns the state before any graphemes have been matched.
+    fn start(&self) -> Self::State;
+
+    /// Advances <code>state</code> past the bytes of a canonicalized grapheme, returning
+    /// <code>false</code> if no word continues with them.
+    ///
+    /// Once this returns <code>false</code>, <code>state</code> isn't used again.
+    fn advance(&self, state: &mut Self::State, grapheme: &[u8]) -> bool;
+
+    /// Returns the payload of the word that ends at <code>state</code>, or
+    /// <code>[None](core::option::Option::None)</code> if no word ends there.
+    fn word_end(&self, state: &Self::State) -> Option<u64>;
+}
+
+impl<'a, D> WordMatcher for &'a Dictionary<D>
+where
+    D: AsRef<[u8]>,
+{
+    type State = (Node<'a>, Output);
+
+    #[inline(always)]
+    fn settings(&self) -> Settings {
+        self.settings
+    }
+
+    #[inline(always)]
+    fn start(&self) -> Self::State {
+        (self.fst.root(), Output::zero())
+    }
+
+    #[inline]
+    fn advance(&self, state: &mut Self::State, grapheme: &[u8]) -> bool {
+        advance(self, state, grapheme)
+    }
+
+    #[inline]
+    fn word_end(&self, state: &Self::State) -> Option<u64> {
+        word_end(state)
+    }
+}
+
+/// Advances a search through the <code>[Fst](fst::raw::Fst)</code> of
+/// <code>dictionary</code>, as with
+/// <code>[WordMatcher::advance](crate::WordMatcher::advance)</code>.
+pub(crate) fn advance<'a, D>(
+    dictionary: &'a Dictionary<D>,
+    (node, output): &mut (Node<'a>, Output),
+    grapheme: &[u8],
+) -> bool
+where
+    D: AsRef<[u8]>,
+{
+    for &byte in grapheme {
+        match node.find_input(byte) {
+            Some(transition_index) => {
+                let transition = node.transition(transition_index);
+                *output = output.cat(transition.out);
+                *node = dictionary.fst.node(transition.addr);
+            }
+            None => return false,
+        }
+    }
+
+    true
+}
+
+/// Returns the value of the word that a search through an
+/// <code>[Fst](fst::raw::Fst)</code> has reached, as with
+/// <code>[WordMatcher::word_end](crate::WordMatcher::word_end)</code>.
+#[inline]
+pub(crate) fn word_end((node, output): &(Node<'_>, Output)) -> Option<u64> {
+    if node.is_final() {
+        Some(output.cat(node.final_output()).value())
+    } else {
+        None
+    }
+}
+
+/// Appends the bytes of a canonicalized grapheme to the graphemes matched so far,
+/// returning <code>false</code> if they aren't UTF-8.
+#[inline]
+pub(crate) fn push_grapheme(prefix: &mut String, grapheme: &[u8]) -> bool {
+    match core::str::from_utf8(grapheme) {
+        Ok(grapheme) => {
+            prefix.push_str(grapheme);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// A set of canonical words, such as a
+/// <code>[BTreeSet](alloc::collections::BTreeSet)&lt;[String](alloc::string::String)&gt;</code>
+/// or a
+/// <code>[HashSet](std::collections::HashSet)&lt;[String](alloc::string::String)&gt;</code>,
+/// with the <code>[Settings](crate::Settings)</code> they were canonicalized with, so
+/// that it can be used as a <code>[WordMatcher](crate::WordMatcher)</code>. Every word
+/// has a payload of zero.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::collections::BTreeSet;
+/// use wordbreaker::{CaseFolding, Settings, WordSegmentations, WordSet};
+///
+/// let settings = Settings::new().with_case_folding(CaseFolding::Full);
+/// let words = ["just", "ice"]
+///     .iter()
+///     .map(|word| settings.canonicalize(word))
+///     .collect::<BTreeSet<_>>();
+/// let matcher = WordSet::new(&words, settings).unwrap();
+///
+/// assert_eq!(
+///     WordSegmentations::from_matcher(matcher, "JustIce").collect::<Vec<_>>(),
+///     [vec!["Just", "Ice"]]
+/// );
+/// ```
+#[derive(Debug)]
+pub struct WordSet<'a, T> {
+    words: &'a T,
+    settings: Settings,
+}
+
+impl<T> Clone for WordSet<'_, T> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for WordSet<'_, T> {}
+
+impl<'a, T> WordSet<'a, T>
+where
+    &'a T: IntoIterator<Item = &'a String>,
+{
+    /// Creates a new <code>[WordSet](crate::WordSet)</code> from <code>words</code>,
+    /// which must already be canonical according to <code>settings</code>.
+    ///
+    /// Every word is checked, and an empty word or one that isn't canonical is an
+    /// <code>[Error](crate::Error)::[InvalidKey](crate::Error::InvalidKey)</code>, as it
+    /// could never be matched.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::collections::BTreeSet;
+    /// use wordbreaker::{Error, Settings, WordSet};
+    ///
+    /// let words = std::iter::once("caf\u{e9}".to_string()).collect::<BTreeSet<_>>();
+    ///
+    /// assert!(matches!(
+    ///     WordSet::new(&words, Settings::new()),
+    ///     Err(Error::InvalidKey { .. })
+    /// ));
+    /// ```
+    pub fn new(words: &'a T, settings: Settings) -> Result<Self, Error> {
+        let mut canonical = String::new();
+        for word in words {
+            canonical.clear();
+            settings.canonicalize_into(word, &mut canonical);
+            if word.is_empty() || canonical != *word {
+                return Err(Error::InvalidKey {
+                    key: word.as_bytes().to_vec(),
+                });
+            }
+        }
+
+        Ok(Self { words, settings })
+    }
+}
+
+impl<T> WordSet<'_, T> {
+    /// Returns the <code>[Settings](crate::Settings)</code> that the words are
+    /// canonical according to.
+    #[inline(always)]
+    pub fn settings(&self) -> Settings {
+        self.settings
+    }
+}
+
+impl WordMatcher for WordSet<'_, BTreeSet<String>> {
+    /// The graphemes matched so far.
+    type State = String;
+
+    #[inline(always)]
+    fn settings(&self) -> Settings {
+        self.settings
+    }
+
+    #[inline(always)]
+    fn start(&self) -> Self::State {
+        String::new()
+    }
+
+    #[inline]
+    fn advance(&self, prefix: &mut Self::State, grapheme: &[u8]) -> bool {
+        push_grapheme(prefix, grapheme)
+            && self
+                .words
+                .range::<str, _>((Bound::Included(prefix.as_str()), Bound::Unbounded))
+                .next()
+                .is_some_and(|word| word.starts_with(prefix.as_str()))
+    }
+
+    #[inline]
+    fn word_end(&self, prefix: &Self::State) -> Option<u64> {
+        self.words.contains(prefix).then_some(0)
+    }
+}
+
+/// A <code>[HashSet](std::collections::HashSet)</code> can't tell whether any word
+/// starts with the graphemes matched so far, so the input is searched to its end from
+/// every position where a word ends. A
+/// <code>[BTreeSet](alloc::collections::BTreeSet)</code> or
+/// <code>[Dictionary](crate::Dictionary)</code> stops as soon as no word can match.
+#[cfg(feature = "std")]
+impl<S> WordMatcher for WordSet<'_, std::collections::HashSet<String, S>>
+where
+    S: core::hash::BuildHasher,
+{
+    /// The graphemes matched so far.
+    type State = String;
+
+    #[inline(always)]
+    fn settings(&self) -> Settings {
+        self.settings
+    }
+
+    #[inline(always)]
+    fn start(&self) -> Self::State {
+        String::new()
+    }
+
+    #[inline]
+    fn advance(&self, prefix: &mut Self::State, grapheme: &[u8]) -> bool {
+        push_grapheme(prefix, grapheme)
+    }
+
+    #[inline]
+    fn word_end(&self, prefix: &Self::State) -> Option<u64> {
+        self.words.contains(prefix).then_some(0)
+    }
+}
@@ -0,0 +1,111 @@
+use memmap2::Mmap;
+use std::fs::File;
+use std::path::Path;
+
+use crate::{Dictionary, Error};
+
+/// # Sharing a mapped dictionary
+///
+/// The file is mapped read-only and shared, so every process that maps the same file
+/// reads the same page-cached bytes instead of loading its own copy, and pages are
+/// only read from disk when a search first touches them.
+///
+/// The bytes have no alignment requirements, as the underlying
+/// <code>[Fst](fst::raw::Fst)</code> reads them a byte at a time, so the same file can
+/// also be loaded with
+/// <code>[Dictionary](crate::Dictionary)::[from_bytes](crate::Dictionary::from_bytes)</code>
+/// or <code>[include_bytes!](core::include_bytes)</code>.
+///
+/// The mapping lives as long as the returned
+/// <code>[Dictionary](crate::Dictionary)</code>, which owns it, and
+/// <code>[WordSegmentations](crate::WordSegmentations)</code> never borrow from it, so
+/// segmentations can outlive the <code>[Dictionary](crate::Dictionary)</code>. On Unix,
+/// the file can be deleted or renamed while it is mapped, so the usual way to update a
+/// shared dictionary is to write a new file and rename it over the old one, which leaves
+/// processes that mapped the old file unaffected until they open it again. Windows
+/// doesn't allow a mapped file to be deleted or replaced.
+impl Dictionary<Mmap> {
+    /// Creates a new <code>[Dictionary](crate::Dictionary)</code> by memory-mapping the
+    /// file at <code>path</code>, which holds the bytes of a prior
+    /// <code>[Dictionary](crate::Dictionary)</code>, <b>without</b> verifying that the
+    /// checksum is correct.
+    ///
+    /// Only the header of the file is checked, so this takes the same time no matter
+    /// how large the file is.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated, by this process or any other, while
+    /// the <code>[Dictionary](crate::Dictionary)</code> exists. Doing so is undefined
+    /// behavior, and on most platforms truncation makes the next search crash the
+    /// process.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Write;
+    /// use wordbreaker::Dictionary;
+    ///
+    /// let mut file = tempfile::NamedTempFile::new().unwrap();
+    /// file.write_all(Dictionary::new(&["just", "ice", "justice"]).as_bytes())
+    ///     .unwrap();
+    ///
+    /// let dictionary = unsafe { Dictionary::open_mmap(file.path()) }.unwrap();
+    /// let mut word_segmentations = dictionary
+    ///     .word_segmentations("justice")
+    ///     .collect::<Vec<_>>();
+    ///
+    /// word_segmentations.sort_unstable();
+    /// assert_eq!(word_segmentations, [vec!["just", "ice"], vec!["justice"]]);
+    /// ```
+    pub unsafe fn open_mmap<P>(path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        Self::from_bytes(map(path.as_ref())?)
+    }
+
+    /// Creates a new <code>[Dictionary](crate::Dictionary)</code> by memory-mapping the
+    /// file at <code>path</code>, verifying it as with
+    /// <code>[Dictionary](crate::Dictionary)::[from_bytes_verified](crate::Dictionary::from_bytes_verified)</code>.
+    ///
+    /// Verifying reads the whole file once, which also brings it into the page cache.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated, by this process or any other, while
+    /// the <code>[Dictionary](crate::Dictionary)</code> exists. Doing so is undefined
+    /// behavior, and on most platforms truncation makes the next search crash the
+    /// process. Verifying the file doesn't make it safe to modify it afterward.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Write;
+    /// use wordbreaker::Dictionary;
+    ///
+    /// let mut file = tempfile::NamedTempFile::new().unwrap();
+    /// file.write_all(Dictionary::new(&["just", "ice", "justice"]).as_bytes())
+    ///     .unwrap();
+    ///
+    /// let dictionary = unsafe { Dictionary::open_mmap_verified(file.path()) }.unwrap();
+    ///
+    /// assert!(dictionary.contains("justice"));
+    /// ```
+    pub unsafe fn open_mmap_verified<P>(path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        Self::from_bytes_verified(map(path.as_ref())?)
+    }
+}
+
+/// Maps the file at <code>path</code> read-only.
+///
+/// # Safety
+///
+/// The file must not be modified while the map exists.
+unsafe fn map(path: &Path) -> Result<Mmap, Error> {
+    let file = File::open(path)?;
+    Ok(Mmap::map(&file)?)
+}
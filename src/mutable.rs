@@ -0,0 +1,378 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::ops::Bound;
+use fst::raw::{Node, Output};
+use fst::Streamer;
+
+use crate::dict::IN_MEMORY_BUILD;
+use crate::matcher::{self, WordMatcher};
+use crate::{
+    Dictionary, DictionaryBuilder, Error, Settings, WordSegmentations,
+    WordSegmentationsWithPayloads,
+};
+
+/// A <code>[Dictionary](crate::Dictionary)</code> that words can be inserted into and
+/// removed from, with each edit visible to the next search.
+///
+/// Edits are kept in sorted maps beside a shared base
+/// <code>[Dictionary](crate::Dictionary)</code> and looked up directly while searching,
+/// so nothing is rebuilt after each edit. As the edits pile up, searches slow down a
+/// little, so they should be merged back into the base from time to time with
+/// <code>[compact](crate::MutableDictionary::compact)</code>, or with
+/// <code>[start_compaction](crate::MutableDictionary::start_compaction)</code> to do the
+/// merging on another thread.
+///
+/// # Examples
+///
+/// ```rust
+/// use wordbreaker::{Dictionary, MutableDictionary};
+///
+/// let mut dictionary = MutableDictionary::new(Dictionary::new(&["just", "ice", "justice"]));
+/// dictionary.remove("justice");
+/// dictionary.insert("cream");
+///
+/// assert_eq!(
+///     dictionary.word_segmentations("justicecream").collect::<Vec<_>>(),
+///     [vec!["just", "ice", "cream"]]
+/// );
+///
+/// dictionary.compact();
+/// assert_eq!(dictionary.pending_edits(), 0);
+/// assert!(dictionary.base().words().eq(["cream", "ice", "just"]));
+/// ```
+#[derive(Clone)]
+pub struct MutableDictionary {
+    /// How many times the base has been replaced, so that a stale
+    /// <code>[Compacted](crate::Compacted)</code> isn't applied.
+    generation: u64,
+    base: Dictionary<Arc<[u8]>>,
+    /// The canonicalized words that aren't in the base, with their weights.
+    additions: BTreeMap<String, u64>,
+    /// The canonicalized words of the base that are hidden.
+    removals: BTreeSet<String>,
+}
+
+impl MutableDictionary {
+    /// Creates a new <code>[MutableDictionary](crate::MutableDictionary)</code> with the
+    /// words of <code>base</code>, which are copied into shared storage.
+    pub fn new<D>(base: Dictionary<D>) -> Self
+    where
+        D: AsRef<[u8]>,
+    {
+        let base = Dictionary::from_bytes(Arc::from(base.as_bytes())).expect(IN_MEMORY_BUILD);
+
+        Self {
+            generation: 0,
+            base,
+            additions: BTreeMap::new(),
+            removals: BTreeSet::new(),
+        }
+    }
+
+    /// Returns the <code>[Settings](crate::Settings)</code> that words are
+    /// canonicalized with.
+    #[inline(always)]
+    pub fn settings(&self) -> Settings {
+        self.base.settings
+    }
+
+    /// Returns the base <code>[Dictionary](crate::Dictionary)</code>, without the edits
+    /// made since it was last compacted.
+    #[inline(always)]
+    pub fn base(&self) -> &Dictionary<Arc<[u8]>> {
+        &self.base
+    }
+
+    /// Returns the number of insertions and removals that haven't been compacted into
+    /// the base yet.
+    #[inline]
+    pub fn pending_edits(&self) -> usize {
+        self.additions.len() + self.removals.len()
+    }
+
+    /// Returns whether <code>word</code> is in this
+    /// <code>[MutableDictionary](crate::MutableDictionary)</code>, after canonicalizing it
+    /// according to its <code>[Settings](crate::Settings)</code>.
+    #[inline]
+    pub fn contains(&self, word: &str) -> bool {
+        if word.is_empty() {
+            return false;
+        }
+
+        let word = self.settings().canonicalize(word);
+        self.additions.contains_key(&word)
+            || (!self.removals.contains(&word) && self.base.fst.contains_key(&word))
+    }
+
+    /// Inserts <code>word</code> with a weight of zero, returning whether it wasn't
+    /// already in this <code>[MutableDictionary](crate::MutableDictionary)</code>.
+    #[inline]
+    pub fn insert(&mut self, word: &str) -> bool {
+        self.insert_weighted(word, 0)
+    }
+
+    /// Inserts <code>word</code> with a <code>weight</code>, returning whether it wasn't
+    /// already in this <code>[MutableDictionary](crate::MutableDictionary)</code>.
+    ///
+    /// A word that is already in the base keeps its weight from the base.
+    pub fn insert_weighted(&mut self, word: &str, weight: u64) -> bool {
+        if word.is_empty() {
+            return false;
+        }
+
+        let word = self.settings().canonicalize(word);
+        if self.removals.remove(&word) {
+            return true;
+        }
+        if self.additions.contains_key(&word) || self.base.fst.contains_key(&word) {
+            return false;
+        }
+
+        self.additions.insert(word, weight);
+        true
+    }
+
+    /// Removes <code>word</code>, returning whether it was in this
+    /// <code>[MutableDictionary](crate::MutableDictionary)</code>.
+    pub fn remove(&mut self, word: &str) -> bool {
+        if word.is_empty() {
+            return false;
+        }
+
+        let word = self.settings().canonicalize(word);
+        if self.additions.remove(&word).is_some() {
+            return true;
+        }
+        if self.removals.contains(&word) || !self.base.fst.contains_key(&word) {
+            return false;
+        }
+
+        self.removals.insert(word);
+        true
+    }
+
+    /// Returns an <code>[Iterator](core::iter::Iterator)</code> over the ways to
+    /// segment <code>input</code> into words of this
+    /// <code>[MutableDictionary](crate::MutableDictionary)</code>, with every edit
+    /// applied, as with
+    /// <code>[Dictionary](crate::Dictionary)::[word_segmentations](crate::Dictionary::word_segmentations)</code>.
+    #[inline]
+    pub fn word_segmentations<'s>(&self, input: &'s str) -> WordSegmentations<'s> {
+        WordSegmentations::with_payloads(self, input, None)
+    }
+
+    /// Returns an <code>[Iterator](core::iter::Iterator)</code> over the segmentations
+    /// of <code>input</code> with the payload of each word, as with
+    /// <code>[Dictionary](crate::Dictionary)::[word_segmentations_with_payloads](crate::Dictionary::word_segmentations_with_payloads)</code>.
+    #[inline]
+    pub fn word_segmentations_with_payloads<'s>(
+        &self,
+        input: &'s str,
+    ) -> WordSegmentationsWithPayloads<'s> {
+        WordSegmentationsWithPayloads::from_matcher(self, input)
+    }
+
+    /// Merges every pending edit into the base.
+    ///
+    /// This takes time proportional to the size of the base. To keep searching and
+    /// editing while it happens, use
+    /// <code>[start_compaction](crate::MutableDictionary::start_compaction)</code>
+    /// instead.
+    #[inline]
+    pub fn compact(&mut self) {
+        let compacted = self.start_compaction().run();
+        self.finish_compaction(compacted);
+    }
+
+    /// Takes a snapshot of the base and the pending edits that can be merged into a new
+    /// base with <code>[Compaction](crate::Compaction)::[run](crate::Compaction::run)</code>,
+    /// which is <code>[Send](core::marker::Send)</code> so that it can run on another
+    /// thread.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::{Dictionary, MutableDictionary};
+    ///
+    /// let mut dictionary = MutableDictionary::new(Dictionary::new(&["just", "ice"]));
+    /// dictionary.insert("justice");
+    ///
+    /// let compaction = dictionary.start_compaction();
+    /// let compacted = std::thread::spawn(move || compaction.run()).join().unwrap();
+    /// // Edits made while compacting are kept.
+    /// dictionary.remove("ice");
+    ///
+    /// assert!(dictionary.finish_compaction(compacted));
+    /// assert!(dictionary.base().words().eq(["ice", "just", "justice"]));
+    /// assert_eq!(dictionary.pending_edits(), 1);
+    /// assert!(!dictionary.contains("ice"));
+    /// ```
+    pub fn start_compaction(&self) -> Compaction {
+        Compaction {
+            generation: self.generation,
+            base: self.base.clone(),
+            additions: self.additions.clone(),
+            removals: self.removals.clone(),
+        }
+    }
+
+    /// Replaces the base with a <code>[Compacted](crate::Compacted)</code> one, keeping
+    /// the edits made since its <code>[Compaction](crate::Compaction)</code> started.
+    ///
+    /// Returns <code>[false](core::primitive::bool)</code> without changing anything if
+    /// the base was replaced after the <code>[Compaction](crate::Compaction)</code>
+    /// started.
+    pub fn finish_compaction(&mut self, compacted: Compacted) -> bool {
+        if compacted.generation != self.generation {
+            return false;
+        }
+
+        // Only words edited before or during the compaction can differ between the
+        // current words and the new base.
+        let edited = compacted
+            .edited
+            .into_iter()
+            .chain(self.additions.keys().cloned())
+            .chain(self.removals.iter().cloned())
+            .collect::<BTreeSet<_>>();
+        let mut additions = BTreeMap::new();
+        let mut removals = BTreeSet::new();
+        for word in edited {
+            let current_weight = match self.additions.get(&word) {
+                Some(&weight) => Some(weight),
+                None if self.removals.contains(&word) => None,
+                None => self.base.fst.get(&word).map(|output| output.value()),
+            };
+            let is_compacted = compacted.base.fst.contains_key(&word);
+            match current_weight {
+                Some(weight) if !is_compacted => {
+                    additions.insert(word, weight);
+                }
+                None if is_compacted => {
+                    removals.insert(word);
+                }
+                _ => {}
+            }
+        }
+
+        self.generation = self.generation.wrapping_add(1);
+        self.base = compacted.base;
+        self.additions = additions;
+        self.removals = removals;
+        true
+    }
+}
+
+impl<'a> WordMatcher for &'a MutableDictionary {
+    /// The search state of the base, or <code>[None](core::option::Option::None)</code>
+    /// once no word of the base continues with the graphemes matched so far, and those
+    /// graphemes, to look up in the pending edits.
+    type State = (Option<(Node<'a>, Output)>, String);
+
+    #[inline(always)]
+    fn settings(&self) -> Settings {
+        self.base.settings
+    }
+
+    #[inline]
+    fn start(&self) -> Self::State {
+        (Some((self.base.fst.root(), Output::zero())), String::new())
+    }
+
+    fn advance(&self, (base, prefix): &mut Self::State, grapheme: &[u8]) -> bool {
+        if let Some(ref mut search) = *base {
+            if !matcher::advance(&self.base, search, grapheme) {
+                *base = None;
+            }
+        }
+        if !matcher::push_grapheme(prefix, grapheme) {
+            return false;
+        }
+
+        base.is_some()
+            || self
+                .additions
+                .range::<str, _>((Bound::Included(prefix.as_str()), Bound::Unbounded))
+                .next()
+                .is_some_and(|(word, _)| word.starts_with(prefix.as_str()))
+    }
+
+    fn word_end(&self, (base, prefix): &Self::State) -> Option<u64> {
+        if let Some(&weight) = self.additions.get(prefix) {
+            return Some(weight);
+        }
+        if self.removals.contains(prefix) {
+            return None;
+        }
+
+        matcher::word_end(base.as_ref()?)
+    }
+}
+
+/// A snapshot of a <code>[MutableDictionary](crate::MutableDictionary)</code> that
+/// <code>[MutableDictionary](crate::MutableDictionary)::[start_compaction](crate::MutableDictionary::start_compaction)</code>
+/// takes, ready to be merged into a new base.
+pub struct Compaction {
+    generation: u64,
+    base: Dictionary<Arc<[u8]>>,
+    additions: BTreeMap<String, u64>,
+    removals: BTreeSet<String>,
+}
+
+impl Compaction {
+    /// Merges the edits into a new base, which is given back to
+    /// <code>[MutableDictionary](crate::MutableDictionary)::[finish_compaction](crate::MutableDictionary::finish_compaction)</code>.
+    pub fn run(self) -> Compacted {
+        let mut builder = DictionaryBuilder::memory(self.base.settings);
+        let bytes = self
+            .merge_into(&mut builder)
+            .and_then(|()| builder.finish())
+            .expect(IN_MEMORY_BUILD);
+        let base = Dictionary::from_bytes(Arc::from(bytes)).expect(IN_MEMORY_BUILD);
+        let edited = self.additions.into_keys().chain(self.removals).collect();
+
+        Compacted {
+            generation: self.generation,
+            base,
+            edited,
+        }
+    }
+
+    /// Inserts the words of the base without the removals, merged in order with the
+    /// additions, into <code>builder</code>.
+    fn merge_into(&self, builder: &mut DictionaryBuilder<Vec<u8>>) -> Result<(), Error> {
+        let mut stream = self.base.fst.stream();
+        let mut additions = self.additions.iter().peekable();
+        while let Some((word, output)) = stream.next() {
+            while let Some((addition, &weight)) =
+                additions.next_if(|(addition, _)| addition.as_bytes() < word)
+            {
+                builder.insert_weighted(addition, weight)?;
+            }
+            let word = word_str(word);
+            if !self.removals.contains(word) {
+                builder.insert_weighted(word, output.value())?;
+            }
+        }
+
+        additions.try_for_each(|(word, &weight)| builder.insert_weighted(word, weight))
+    }
+}
+
+/// A new base for a <code>[MutableDictionary](crate::MutableDictionary)</code> that
+/// <code>[Compaction](crate::Compaction)::[run](crate::Compaction::run)</code> built.
+pub struct Compacted {
+    generation: u64,
+    base: Dictionary<Arc<[u8]>>,
+    /// The words that were edited when the compaction started.
+    edited: Vec<String>,
+}
+
+/// Returns <code>word</code> as a <code>[str](core::primitive::str)</code>, or an empty
+/// one if it isn't UTF-8, which no word is.
+#[inline]
+fn word_str(word: &[u8]) -> &str {
+    core::str::from_utf8(word).unwrap_or_default()
+}
@@ -0,0 +1,216 @@
+use alloc::vec::Vec;
+use core::fmt;
+use fst::raw::{IndexedValue, OpBuilder};
+use fst::Streamer;
+
+use crate::dict::IN_MEMORY_BUILD;
+use crate::{Dictionary, DictionaryBuilder, Settings, ValueKind};
+
+/// The error returned when combining two
+/// <code>[Dictionary](crate::Dictionary)</code>s that were built with different
+/// <code>[Settings](crate::Settings)</code>, as their words are not comparable.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SettingsMismatch {
+    /// The <code>[Settings](crate::Settings)</code> of the
+    /// <code>[Dictionary](crate::Dictionary)</code> the method was called on.
+    pub left: Settings,
+    /// The <code>[Settings](crate::Settings)</code> of the other
+    /// <code>[Dictionary](crate::Dictionary)</code>.
+    pub right: Settings,
+}
+
+impl fmt::Display for SettingsMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cannot combine dictionaries with different settings ({:?} and {:?})",
+            self.left, self.right
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SettingsMismatch {}
+
+impl<D> Dictionary<D>
+where
+    D: AsRef<[u8]>,
+{
+    /// Creates a new
+    /// <code>[Dictionary](crate::Dictionary)&lt;[Vec](alloc::vec::Vec)&lt;[u8](core::primitive::u8)&gt;&gt;</code>
+    /// with the words that are in either <code>self</code> or <code>other</code>.
+    ///
+    /// The words are streamed out of both underlying <code>[Fst](fst::raw::Fst)</code>s
+    /// without being canonicalized again, so both must have the same
+    /// <code>[Settings](crate::Settings)</code>. The weights of words in both are added
+    /// together, or if the <code>[Settings](crate::Settings)</code> have a
+    /// <code>[ValueKind](crate::ValueKind)</code> of
+    /// <code>[Payloads](crate::ValueKind::Payloads)</code>, the payloads of
+    /// <code>self</code> are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::Dictionary;
+    ///
+    /// let base = Dictionary::new(&["just", "ice"]);
+    /// let domain = Dictionary::new(&["justice"]);
+    /// let dictionary = base.union(&domain).unwrap();
+    ///
+    /// let mut word_segmentations = dictionary
+    ///     .word_segmentations("justice")
+    ///     .collect::<Vec<_>>();
+    ///
+    /// word_segmentations.sort_unstable();
+    /// assert_eq!(word_segmentations, [vec!["just", "ice"], vec!["justice"]]);
+    /// ```
+    pub fn union<E>(&self, other: &Dictionary<E>) -> Result<Dictionary<Vec<u8>>, SettingsMismatch>
+    where
+        E: AsRef<[u8]>,
+    {
+        self.combine(other, |op| build(self.settings, op.union()))
+    }
+
+    /// Creates a new
+    /// <code>[Dictionary](crate::Dictionary)&lt;[Vec](alloc::vec::Vec)&lt;[u8](core::primitive::u8)&gt;&gt;</code>
+    /// with the words that are in both <code>self</code> and <code>other</code>.
+    ///
+    /// Both must have the same <code>[Settings](crate::Settings)</code>. The weights of
+    /// the words are added together, or if the <code>[Settings](crate::Settings)</code>
+    /// have a <code>[ValueKind](crate::ValueKind)</code> of
+    /// <code>[Payloads](crate::ValueKind::Payloads)</code>, the payloads of
+    /// <code>self</code> are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::Dictionary;
+    ///
+    /// let base = Dictionary::new(&["just", "ice", "justice"]);
+    /// let domain = Dictionary::new(&["ice", "justice", "cream"]);
+    /// let dictionary = base.intersection(&domain).unwrap();
+    ///
+    /// assert_eq!(
+    ///     dictionary.word_segmentations("justice").collect::<Vec<_>>(),
+    ///     [vec!["justice"]]
+    /// );
+    /// ```
+    pub fn intersection<E>(
+        &self,
+        other: &Dictionary<E>,
+    ) -> Result<Dictionary<Vec<u8>>, SettingsMismatch>
+    where
+        E: AsRef<[u8]>,
+    {
+        self.combine(other, |op| build(self.settings, op.intersection()))
+    }
+
+    /// Creates a new
+    /// <code>[Dictionary](crate::Dictionary)&lt;[Vec](alloc::vec::Vec)&lt;[u8](core::primitive::u8)&gt;&gt;</code>
+    /// with the words that are in <code>self</code> but not in <code>other</code>.
+    ///
+    /// Both must have the same <code>[Settings](crate::Settings)</code>.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::Dictionary;
+    ///
+    /// let base = Dictionary::new(&["just", "ice", "justice"]);
+    /// let blocklist = Dictionary::new(&["justice"]);
+    /// let dictionary = base.difference(&blocklist).unwrap();
+    ///
+    /// assert_eq!(
+    ///     dictionary.word_segmentations("justice").collect::<Vec<_>>(),
+    ///     [vec!["just", "ice"]]
+    /// );
+    /// ```
+    pub fn difference<E>(
+        &self,
+        other: &Dictionary<E>,
+    ) -> Result<Dictionary<Vec<u8>>, SettingsMismatch>
+    where
+        E: AsRef<[u8]>,
+    {
+        self.combine(other, |op| build(self.settings, op.difference()))
+    }
+
+    /// Creates a new
+    /// <code>[Dictionary](crate::Dictionary)&lt;[Vec](alloc::vec::Vec)&lt;[u8](core::primitive::u8)&gt;&gt;</code>
+    /// with the words that are in exactly one of <code>self</code> and
+    /// <code>other</code>.
+    ///
+    /// Both must have the same <code>[Settings](crate::Settings)</code>.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::Dictionary;
+    ///
+    /// let first = Dictionary::new(&["just", "ice", "justice"]);
+    /// let second = Dictionary::new(&["ice", "justice", "cream"]);
+    /// let dictionary = first.symmetric_difference(&second).unwrap();
+    ///
+    /// assert_eq!(
+    ///     dictionary.word_segmentations("justcream").collect::<Vec<_>>(),
+    ///     [vec!["just", "cream"]]
+    /// );
+    /// assert_eq!(dictionary.word_segmentations("justice").count(), 0);
+    /// ```
+    pub fn symmetric_difference<E>(
+        &self,
+        other: &Dictionary<E>,
+    ) -> Result<Dictionary<Vec<u8>>, SettingsMismatch>
+    where
+        E: AsRef<[u8]>,
+    {
+        self.combine(other, |op| build(self.settings, op.symmetric_difference()))
+    }
+
+    /// Checks that <code>self</code> and <code>other</code> have the same
+    /// <code>[Settings](crate::Settings)</code> and then runs <code>f</code> on an
+    /// <code>[OpBuilder](fst::raw::OpBuilder)</code> over both of them.
+    fn combine<'f, E, F>(
+        &'f self,
+        other: &'f Dictionary<E>,
+        f: F,
+    ) -> Result<Dictionary<Vec<u8>>, SettingsMismatch>
+    where
+        E: AsRef<[u8]>,
+        F: FnOnce(OpBuilder<'f>) -> Dictionary<Vec<u8>>,
+    {
+        if self.settings != other.settings {
+            return Err(SettingsMismatch {
+                left: self.settings,
+                right: other.settings,
+            });
+        }
+
+        Ok(f(self.fst.op().add(&other.fst)))
+    }
+}
+
+/// Builds a <code>[Dictionary](crate::Dictionary)</code> from the sorted words of a set
+/// operation's <code>stream</code>, adding together the weights of each word or keeping
+/// the payload of the first <code>[Dictionary](crate::Dictionary)</code> that has it.
+fn build<S>(settings: Settings, mut stream: S) -> Dictionary<Vec<u8>>
+where
+    S: for<'a> Streamer<'a, Item = (&'a [u8], &'a [IndexedValue])>,
+{
+    let mut builder = DictionaryBuilder::memory(settings);
+    while let Some((word, values)) = stream.next() {
+        let value = match settings.value_kind() {
+            ValueKind::Weights => values
+                .iter()
+                .fold(0_u64, |weight, value| weight.saturating_add(value.value)),
+            ValueKind::Payloads => values
+                .iter()
+                .min_by_key(|value| value.index)
+                .map_or(0, |value| value.value),
+        };
+        // The words come out of the stream sorted and without repeats.
+        builder.insert_bytes(word, value).expect(IN_MEMORY_BUILD);
+    }
+
+    builder.into_dictionary().expect(IN_MEMORY_BUILD)
+}
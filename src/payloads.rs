@@ -0,0 +1,212 @@
+use alloc::collections::BTreeMap;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::dict;
+use crate::matcher::WordMatcher;
+use crate::{Dictionary, DictionaryBuilder, Error, Settings, ValueKind, WordSegmentations};
+
+/// The <code>[Iterator](core::iter::Iterator)</code> that
+/// <code>[Dictionary](crate::Dictionary)::[word_segmentations_with_payloads](crate::Dictionary::word_segmentations_with_payloads)</code>
+/// produces.
+///
+/// It yields the same segmentations in the same order as
+/// <code>[WordSegmentations](crate::WordSegmentations)</code>, with each word paired
+/// with its payload.
+#[derive(Clone, Debug)]
+pub struct WordSegmentationsWithPayloads<'s> {
+    input: &'s str,
+    segmentations: WordSegmentations<'s>,
+    payloads: Arc<BTreeMap<(usize, usize), u64>>,
+}
+
+impl<'s> WordSegmentationsWithPayloads<'s> {
+    /// Returns an <code>[Iterator](core::iter::Iterator)</code> over the ways to
+    /// segment <code>input</code> into words of <code>matcher</code> with the payload
+    /// of each word, as with
+    /// <code>[Dictionary](crate::Dictionary)::[word_segmentations_with_payloads](crate::Dictionary::word_segmentations_with_payloads)</code>.
+    pub fn from_matcher<M>(matcher: M, input: &'s str) -> Self
+    where
+        M: WordMatcher,
+    {
+        let mut payloads = BTreeMap::new();
+        let segmentations = WordSegmentations::with_payloads(matcher, input, Some(&mut payloads));
+
+        Self {
+            input,
+            segmentations,
+            payloads: Arc::new(payloads),
+        }
+    }
+
+    /// Pairs each word of <code>segmentation</code> with the payload recorded for its
+    /// byte range in the input.
+    fn with_payloads(&self, segmentation: Vec<&'s str>) -> Vec<(&'s str, u64)> {
+        segmentation
+            .into_iter()
+            .map(|word| {
+                // Every word is a slice of the input, so its position can be recovered
+                // from its address.
+                let start = (word.as_ptr() as usize).wrapping_sub(self.input.as_ptr() as usize);
+                let end = start.wrapping_add(word.len());
+                (word, self.payloads.get(&(start, end)).copied().unwrap_or(0))
+            })
+            .collect()
+    }
+}
+
+impl<'s> Iterator for WordSegmentationsWithPayloads<'s> {
+    type Item = Vec<(&'s str, u64)>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let segmentation = self.segmentations.next()?;
+        Some(self.with_payloads(segmentation))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.segmentations.size_hint()
+    }
+}
+
+impl<'s> DoubleEndedIterator for WordSegmentationsWithPayloads<'s> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let segmentation = self.segmentations.next_back()?;
+        Some(self.with_payloads(segmentation))
+    }
+}
+
+impl<'s> core::iter::FusedIterator for WordSegmentationsWithPayloads<'s> {}
+
+impl Dictionary<Vec<u8>> {
+    /// Creates a new
+    /// <code>[Dictionary](crate::Dictionary)&lt;[Vec](alloc::vec::Vec)&lt;[u8](core::primitive::u8)&gt;&gt;</code>
+    /// from pairs of words and payloads, such as part-of-speech tags or IDs,
+    /// canonicalizing the words according to the given
+    /// <code>[Settings](crate::Settings)</code>.
+    ///
+    /// Payloads are stored in the same place as the weights of
+    /// <code>[with_weights](crate::Dictionary::with_weights)</code>, so a
+    /// <code>[Dictionary](crate::Dictionary)</code> has one or the other, and the
+    /// <code>[Settings](crate::Settings)</code> of the result have a
+    /// <code>[ValueKind](crate::ValueKind)</code> of
+    /// <code>[Payloads](crate::ValueKind::Payloads)</code>. Words that are the same after
+    /// canonicalization must have the same payload.
+    ///
+    /// # Panics
+    ///
+    /// Panics if two words that are the same after canonicalization have different
+    /// payloads. Use
+    /// <code>[Dictionary](crate::Dictionary)::[try_with_payloads](crate::Dictionary::try_with_payloads)</code>
+    /// to get an <code>[Error](crate::Error)</code> instead.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::{Dictionary, Settings};
+    ///
+    /// let dictionary =
+    ///     Dictionary::with_payloads([("just", 1), ("ice", 2), ("justice", 3)], Settings::new());
+    ///
+    /// assert_eq!(dictionary.payload("ice"), Some(2));
+    /// ```
+    pub fn with_payloads<I, S>(words: I, settings: Settings) -> Self
+    where
+        I: IntoIterator<Item = (S, u64)>,
+        S: AsRef<str>,
+    {
+        let builder = DictionaryBuilder::memory(settings.with_value_kind(ValueKind::Payloads));
+        dict::build_in_memory(builder, words).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Creates a new
+    /// <code>[Dictionary](crate::Dictionary)&lt;[Vec](alloc::vec::Vec)&lt;[u8](core::primitive::u8)&gt;&gt;</code>
+    /// from pairs of words and payloads, as with
+    /// <code>[Dictionary](crate::Dictionary)::[with_payloads](crate::Dictionary::with_payloads)</code>,
+    /// but returning an <code>[Error](crate::Error)</code> instead of panicking if a word
+    /// has different payloads, and for words that could never be found, as with
+    /// <code>[try_with_settings](crate::Dictionary::try_with_settings)</code>.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::{Dictionary, Error, Settings};
+    ///
+    /// let result = Dictionary::try_with_payloads([("ice", 1), ("ICE", 2)], Settings::new());
+    /// assert!(result.is_ok());
+    ///
+    /// let result = Dictionary::try_with_payloads([("ice", 1), ("ice", 2)], Settings::new());
+    /// assert!(matches!(result, Err(Error::ConflictingPayloads { first: 1, second: 2, .. })));
+    /// ```
+    pub fn try_with_payloads<I, S>(words: I, settings: Settings) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = (S, u64)>,
+        S: AsRef<str>,
+    {
+        let builder = DictionaryBuilder::memory(settings.with_value_kind(ValueKind::Payloads))
+            .rejecting_invalid_words();
+        dict::build_in_memory(builder, words)
+    }
+}
+
+impl<D> Dictionary<D>
+where
+    D: AsRef<[u8]>,
+{
+    /// Returns the payload of <code>word</code>, or
+    /// <code>[None](core::option::Option::None)</code> if it isn't in this
+    /// <code>[Dictionary](crate::Dictionary)</code>.
+    ///
+    /// This is the same value as <code>[weight](crate::Dictionary::weight)</code>, for
+    /// dictionaries built with
+    /// <code>[with_payloads](crate::Dictionary::with_payloads)</code>.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::{Dictionary, Settings};
+    ///
+    /// let dictionary = Dictionary::with_payloads([("just", 1), ("ice", 2)], Settings::new());
+    ///
+    /// assert_eq!(dictionary.payload("just"), Some(1));
+    /// assert_eq!(dictionary.payload("justice"), None);
+    /// ```
+    #[inline(always)]
+    pub fn payload(&self, word: &str) -> Option<u64> {
+        self.weight(word)
+    }
+
+    /// Returns an <code>[Iterator](core::iter::Iterator)</code> over the same
+    /// segmentations as
+    /// <code>[word_segmentations](crate::Dictionary::word_segmentations)</code>, but with
+    /// each word paired with its payload.
+    ///
+    /// The payloads are collected while <code>input</code> is searched, so no word needs
+    /// to be looked up again.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::{Dictionary, Settings};
+    ///
+    /// let dictionary =
+    ///     Dictionary::with_payloads([("just", 1), ("ice", 2), ("justice", 3)], Settings::new());
+    /// let mut word_segmentations = dictionary
+    ///     .word_segmentations_with_payloads("justice")
+    ///     .collect::<Vec<_>>();
+    ///
+    /// word_segmentations.sort_unstable();
+    /// assert_eq!(
+    ///     word_segmentations,
+    ///     [vec![("just", 1), ("ice", 2)], vec![("justice", 3)]]
+    /// );
+    /// ```
+    pub fn word_segmentations_with_payloads<'s>(
+        &self,
+        input: &'s str,
+    ) -> WordSegmentationsWithPayloads<'s> {
+        WordSegmentationsWithPayloads::from_matcher(self, input)
+    }
+}
@@ -0,0 +1,171 @@
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::dict::IN_MEMORY_BUILD;
+use crate::{Dictionary, DictionaryBuilder, Settings};
+
+/// What <code>[Dictionary](crate::Dictionary)::[with_report](crate::Dictionary::with_report)</code>
+/// reports about the words it was given, so that word lists can be fixed at their
+/// source.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BuildReport {
+    /// The indexes of the words that were empty, which were dropped.
+    pub empty_words: Vec<usize>,
+    /// The words that were spelled differently but became the same word after
+    /// canonicalization, sorted by that word.
+    pub merged_words: Vec<MergedWords>,
+    /// The words that were kept but are likely mistakes, in the order they were given.
+    pub suspicious_words: Vec<SuspiciousWord>,
+}
+
+impl BuildReport {
+    /// Returns whether nothing was dropped, merged or found suspicious.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.empty_words.is_empty()
+            && self.merged_words.is_empty()
+            && self.suspicious_words.is_empty()
+    }
+}
+
+/// Differently spelled words that became one word after canonicalization.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MergedWords {
+    /// The canonical form of the words, which is stored in the
+    /// <code>[Dictionary](crate::Dictionary)</code>.
+    pub word: String,
+    /// Each spelling of the word, in the order they were first given.
+    pub spellings: Vec<String>,
+}
+
+/// A word that was kept but is likely a mistake.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SuspiciousWord {
+    /// The index of the word.
+    pub index: usize,
+    /// The word as it was given.
+    pub word: String,
+    /// Everything that is suspicious about the word.
+    pub suspicions: Vec<Suspicion>,
+}
+
+/// Why a <code>[SuspiciousWord](crate::SuspiciousWord)</code> is suspicious.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Suspicion {
+    /// The word contains whitespace, so it is probably more than one word.
+    Whitespace,
+    /// The word contains control characters.
+    Control,
+    /// The word starts or ends partway through an extended grapheme cluster, such as
+    /// with a lone combining mark, so it combines with the text around it and can never
+    /// be found by
+    /// <code>[Dictionary](crate::Dictionary)::[word_segmentations](crate::Dictionary::word_segmentations)</code>.
+    PartialGrapheme,
+}
+
+/// Returns everything that is suspicious about <code>word</code>.
+fn suspicions(word: &str) -> Vec<Suspicion> {
+    let mut suspicions = Vec::new();
+    if word.chars().any(char::is_whitespace) {
+        suspicions.push(Suspicion::Whitespace);
+    }
+    if word.chars().any(char::is_control) {
+        suspicions.push(Suspicion::Control);
+    }
+    if has_partial_grapheme(word) {
+        suspicions.push(Suspicion::PartialGrapheme);
+    }
+
+    suspicions
+}
+
+/// Returns whether <code>word</code> starts or ends partway through an extended
+/// grapheme cluster.
+pub(crate) fn has_partial_grapheme(word: &str) -> bool {
+    // A word made of whole graphemes doesn't combine with a letter on either side.
+    let graphemes = word.graphemes(true).count();
+    let mut surrounded = String::with_capacity(word.len() + 2);
+    surrounded.push('a');
+    surrounded.push_str(word);
+    surrounded.push('a');
+    surrounded.graphemes(true).count() != graphemes + 2
+}
+
+impl Dictionary<Vec<u8>> {
+    /// Creates a new
+    /// <code>[Dictionary](crate::Dictionary)&lt;[Vec](alloc::vec::Vec)&lt;[u8](core::primitive::u8)&gt;&gt;</code>
+    /// from its <code>words</code>, as with
+    /// <code>[Dictionary](crate::Dictionary)::[with_settings](crate::Dictionary::with_settings)</code>,
+    /// along with a <code>[BuildReport](crate::BuildReport)</code> of the words that
+    /// were dropped, merged or found suspicious.
+    ///
+    /// Every word is held in memory with all of its spellings, so this needs more memory
+    /// than <code>[with_settings](crate::Dictionary::with_settings)</code>.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::{Dictionary, Settings, Suspicion};
+    ///
+    /// let (dictionary, report) = Dictionary::with_report(
+    ///     ["café", "", "cafe\u{301}", "ice cream", "\u{301}"],
+    ///     Settings::new(),
+    /// );
+    ///
+    /// assert_eq!(dictionary.len(), 3);
+    /// assert_eq!(report.empty_words, [1]);
+    /// assert_eq!(report.merged_words[0].spellings, ["café", "cafe\u{301}"]);
+    /// assert_eq!(report.suspicious_words[0].index, 3);
+    /// assert_eq!(report.suspicious_words[0].suspicions, [Suspicion::Whitespace]);
+    /// assert_eq!(
+    ///     report.suspicious_words[1].suspicions,
+    ///     [Suspicion::PartialGrapheme]
+    /// );
+    /// ```
+    pub fn with_report<I>(words: I, settings: Settings) -> (Self, BuildReport)
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let mut report = BuildReport::default();
+        let mut spellings = BTreeMap::<String, Vec<String>>::new();
+
+        for (index, word) in words.into_iter().enumerate() {
+            let word = word.as_ref();
+            if word.is_empty() {
+                report.empty_words.push(index);
+                continue;
+            }
+
+            let suspicions = suspicions(word);
+            if !suspicions.is_empty() {
+                report.suspicious_words.push(SuspiciousWord {
+                    index,
+                    word: word.to_string(),
+                    suspicions,
+                });
+            }
+
+            let word_spellings = spellings.entry(settings.canonicalize(word)).or_default();
+            if !word_spellings.iter().any(|spelling| spelling == word) {
+                word_spellings.push(word.to_string());
+            }
+        }
+
+        let mut builder = DictionaryBuilder::memory(settings);
+        let dictionary = builder
+            .extend(spellings.keys())
+            .and_then(|()| builder.into_dictionary())
+            .expect(IN_MEMORY_BUILD);
+
+        report.merged_words = spellings
+            .into_iter()
+            .filter(|(_, spellings)| spellings.len() > 1)
+            .map(|(word, spellings)| MergedWords { word, spellings })
+            .collect();
+
+        (dictionary, report)
+    }
+}
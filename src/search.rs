@@ -0,0 +1,694 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use fst::raw::Stream;
+use fst::{Automaton, IntoStreamer, Streamer};
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{Dictionary, Settings};
+
+/// A pattern that
+/// <code>[Dictionary](crate::Dictionary)::[search](crate::Dictionary::search)</code>
+/// matches whole words against, one grapheme at a time.
+///
+/// Patterns are written either as globs, with
+/// <code>[Pattern](crate::Pattern)::[glob](crate::Pattern::glob)</code>, or in a subset of
+/// regular expression syntax, with
+/// <code>[Pattern](crate::Pattern)::[regex](crate::Pattern::regex)</code>. Their literal
+/// parts are canonicalized with the <code>[Settings](crate::Settings)</code> of the
+/// <code>[Dictionary](crate::Dictionary)</code> being searched, so a pattern matches the
+/// same words however they were capitalized or normalized. A range in a class, such as
+/// <code>&#91;à-ÿ&#93;</code>, matches a grapheme that composes to a single character
+/// in it, so accented letters stored in NFD are matched too.
+#[derive(Clone, Debug)]
+pub struct Pattern {
+    node: Node,
+}
+
+/// The error returned when a <code>[Pattern](crate::Pattern)</code> can't be parsed. Each
+/// variant holds the byte position in the pattern where the problem was found.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PatternError {
+    /// A <code>\\</code> ends the pattern without escaping anything.
+    TrailingBackslash {
+        /// Where the <code>\\</code> is.
+        position: usize,
+    },
+    /// A <code>[</code> is never closed by a <code>]</code>.
+    UnclosedClass {
+        /// Where the <code>[</code> is.
+        position: usize,
+    },
+    /// A range in a class has an end that is not a single character, or ends before it
+    /// starts.
+    InvalidRange {
+        /// Where the range starts.
+        position: usize,
+    },
+    /// A <code>(</code> is never closed by a <code>)</code>.
+    UnclosedGroup {
+        /// Where the <code>(</code> is.
+        position: usize,
+    },
+    /// A <code>)</code> doesn't close any <code>(</code>.
+    UnopenedGroup {
+        /// Where the <code>)</code> is.
+        position: usize,
+    },
+    /// A <code>*</code>, <code>+</code> or <code>?</code> doesn't follow anything that it
+    /// could repeat.
+    NothingToRepeat {
+        /// Where the repetition is.
+        position: usize,
+    },
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatternError::TrailingBackslash { position } => {
+                write!(f, "trailing backslash at byte {} of pattern", position)
+            }
+            PatternError::UnclosedClass { position } => {
+                write!(f, "unclosed [ at byte {} of pattern", position)
+            }
+            PatternError::InvalidRange { position } => {
+                write!(f, "invalid range at byte {} of pattern", position)
+            }
+            PatternError::UnclosedGroup { position } => {
+                write!(f, "unclosed ( at byte {} of pattern", position)
+            }
+            PatternError::UnopenedGroup { position } => {
+                write!(f, "unopened ) at byte {} of pattern", position)
+            }
+            PatternError::NothingToRepeat { position } => {
+                write!(f, "nothing to repeat at byte {} of pattern", position)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PatternError {}
+
+/// A parsed <code>[Pattern](crate::Pattern)</code>, with its literals not yet
+/// canonicalized.
+#[derive(Clone, Debug)]
+enum Node {
+    /// Matches these graphemes.
+    Literal(String),
+    /// Matches any one grapheme.
+    Any,
+    /// Matches one grapheme that is, or with <code>negated</code> is not, in the class.
+    Class {
+        negated: bool,
+        items: Vec<ClassItem>,
+    },
+    Concat(Vec<Node>),
+    Alternation(Vec<Node>),
+    /// Matches zero or more of the node.
+    Star(Box<Node>),
+    /// Matches one or more of the node.
+    Plus(Box<Node>),
+    /// Matches zero or one of the node.
+    Question(Box<Node>),
+}
+
+#[derive(Clone, Debug)]
+enum ClassItem {
+    Grapheme(String),
+    Range(char, char),
+}
+
+/// The graphemes of a pattern being parsed, with their byte positions.
+struct Parser<'p> {
+    graphemes: Vec<(usize, &'p str)>,
+    index: usize,
+    len: usize,
+}
+
+impl<'p> Parser<'p> {
+    fn new(pattern: &'p str) -> Self {
+        Self {
+            graphemes: pattern.grapheme_indices(true).collect(),
+            index: 0,
+            len: pattern.len(),
+        }
+    }
+
+    #[inline]
+    fn peek(&self) -> Option<&'p str> {
+        self.graphemes
+            .get(self.index)
+            .map(|&(_, grapheme)| grapheme)
+    }
+
+    #[inline]
+    fn position(&self) -> usize {
+        self.graphemes
+            .get(self.index)
+            .map_or(self.len, |&(position, _)| position)
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<&'p str> {
+        let grapheme = self.peek()?;
+        self.index = self.index.wrapping_add(1);
+        Some(grapheme)
+    }
+
+    /// Parses the grapheme after a <code>\\</code>.
+    fn escaped(&mut self, position: usize) -> Result<&'p str, PatternError> {
+        self.next()
+            .ok_or(PatternError::TrailingBackslash { position })
+    }
+
+    /// Parses a class after its <code>[</code>, which was at <code>start</code>.
+    fn class(&mut self, start: usize) -> Result<Node, PatternError> {
+        let negated = matches!(self.peek(), Some("!") | Some("^"));
+        if negated {
+            self.next();
+        }
+
+        let mut items = Vec::new();
+        loop {
+            let position = self.position();
+            let grapheme = match self.next() {
+                None => return Err(PatternError::UnclosedClass { position: start }),
+                // A `]` first in the class is part of it.
+                Some("]") if !items.is_empty() => break,
+                Some("\\") => self.escaped(position)?,
+                Some(grapheme) => grapheme,
+            };
+
+            let is_range = self.peek() == Some("-")
+                && !matches!(
+                    self.graphemes.get(self.index.wrapping_add(1)),
+                    None | Some((_, "]"))
+                );
+            if !is_range {
+                items.push(ClassItem::Grapheme(grapheme.to_string()));
+                continue;
+            }
+
+            self.next();
+            let end_position = self.position();
+            let end = match self.next() {
+                Some("\\") => self.escaped(end_position)?,
+                Some(end) => end,
+                None => return Err(PatternError::UnclosedClass { position: start }),
+            };
+            match (composed_char(grapheme), composed_char(end)) {
+                (Some(low), Some(high)) if low <= high => items.push(ClassItem::Range(low, high)),
+                _ => return Err(PatternError::InvalidRange { position }),
+            }
+        }
+
+        Ok(Node::Class { negated, items })
+    }
+
+    fn glob(&mut self) -> Result<Node, PatternError> {
+        let mut nodes = Vec::new();
+        while let Some(grapheme) = self.peek() {
+            let position = self.position();
+            self.next();
+            nodes.push(match grapheme {
+                "*" => Node::Star(Box::new(Node::Any)),
+                "?" => Node::Any,
+                "[" => self.class(position)?,
+                "\\" => Node::Literal(self.escaped(position)?.to_string()),
+                grapheme => Node::Literal(grapheme.to_string()),
+            });
+        }
+
+        Ok(Node::Concat(nodes))
+    }
+
+    fn alternation(&mut self) -> Result<Node, PatternError> {
+        let mut alternatives = vec![self.concat()?];
+        while self.peek() == Some("|") {
+            self.next();
+            alternatives.push(self.concat()?);
+        }
+
+        Ok(if alternatives.len() == 1 {
+            alternatives.pop().unwrap_or(Node::Concat(Vec::new()))
+        } else {
+            Node::Alternation(alternatives)
+        })
+    }
+
+    fn concat(&mut self) -> Result<Node, PatternError> {
+        let mut nodes = Vec::new();
+        while let Some(grapheme) = self.peek() {
+            let position = self.position();
+            let node = match grapheme {
+                "|" | ")" => break,
+                "*" | "+" | "?" => {
+                    let node = nodes
+                        .pop()
+                        .ok_or(PatternError::NothingToRepeat { position })?;
+                    self.next();
+                    nodes.push(match grapheme {
+                        "*" => Node::Star(Box::new(node)),
+                        "+" => Node::Plus(Box::new(node)),
+                        _ => Node::Question(Box::new(node)),
+                    });
+                    continue;
+                }
+                // Words are always matched whole, so anchors change nothing.
+                "^" if self.index == 0 => {
+                    self.next();
+                    continue;
+                }
+                "$" if self.index.wrapping_add(1) == self.graphemes.len() => {
+                    self.next();
+                    continue;
+                }
+                _ => {
+                    self.next();
+                    match grapheme {
+                        "." => Node::Any,
+                        "[" => self.class(position)?,
+                        "(" => {
+                            let node = self.alternation()?;
+                            if self.next() != Some(")") {
+                                return Err(PatternError::UnclosedGroup { position });
+                            }
+                            node
+                        }
+                        "\\" => Node::Literal(self.escaped(position)?.to_string()),
+                        grapheme => Node::Literal(grapheme.to_string()),
+                    }
+                }
+            };
+            nodes.push(node);
+        }
+
+        Ok(Node::Concat(nodes))
+    }
+}
+
+impl Pattern {
+    /// Parses a glob pattern.
+    ///
+    /// | Syntax                      | Matches                                                |
+    /// |-----------------------------|--------------------------------------------------------|
+    /// | <code>?</code>              | Any one grapheme                                       |
+    /// | <code>*</code>              | Any number of graphemes, including none                |
+    /// | <code>&#91;abc&#93;</code>  | One of the graphemes listed                            |
+    /// | <code>&#91;a-z&#93;</code>  | One grapheme that composes to a character in the range |
+    /// | <code>&#91;!abc&#93;</code> | One grapheme that is not listed                        |
+    /// | <code>\\*</code>            | The grapheme after the backslash                       |
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::{Dictionary, Pattern};
+    ///
+    /// let dictionary = Dictionary::new(&["cat", "cats", "cot", "cut", "dot"]);
+    /// let pattern = Pattern::glob("c[!u]t*").unwrap();
+    ///
+    /// assert_eq!(
+    ///     dictionary.search(&pattern).collect::<Vec<_>>(),
+    ///     ["cat", "cats", "cot"]
+    /// );
+    /// ```
+    #[inline]
+    pub fn glob(pattern: &str) -> Result<Self, PatternError> {
+        Ok(Self {
+            node: Parser::new(pattern).glob()?,
+        })
+    }
+
+    /// Parses a pattern in a subset of regular expression syntax.
+    ///
+    /// | Syntax                      | Matches                                                |
+    /// |-----------------------------|--------------------------------------------------------|
+    /// | <code>.</code>              | Any one grapheme                                       |
+    /// | <code>&#91;abc&#93;</code>  | One of the graphemes listed                            |
+    /// | <code>&#91;a-z&#93;</code>  | One grapheme that composes to a character in the range |
+    /// | <code>&#91;^abc&#93;</code> | One grapheme that is not listed                        |
+    /// | <code>x*</code>             | Zero or more of <code>x</code>                         |
+    /// | <code>x+</code>             | One or more of <code>x</code>                          |
+    /// | <code>x?</code>             | Zero or one of <code>x</code>                          |
+    /// | <code>x\|y</code>           | Either <code>x</code> or <code>y</code>                |
+    /// | <code>(x)</code>            | <code>x</code>, grouped                                |
+    /// | <code>\\.</code>            | The grapheme after the backslash                       |
+    ///
+    /// Repetitions apply to a whole grapheme, so <code>é+</code> repeats "é" even when
+    /// it is stored in NFD form. Patterns always match whole words, so a leading
+    /// <code>^</code> and a trailing <code>$</code> are accepted and ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::{Dictionary, Pattern};
+    ///
+    /// let dictionary = Dictionary::new(&["cat", "cats", "cot", "cut", "dot"]);
+    /// let pattern = Pattern::regex("(c|d)o.|cats?").unwrap();
+    ///
+    /// assert_eq!(
+    ///     dictionary.search(&pattern).collect::<Vec<_>>(),
+    ///     ["cat", "cats", "cot", "dot"]
+    /// );
+    /// ```
+    pub fn regex(pattern: &str) -> Result<Self, PatternError> {
+        let mut parser = Parser::new(pattern);
+        let node = parser.alternation()?;
+        match parser.peek() {
+            Some(_) => Err(PatternError::UnopenedGroup {
+                position: parser.position(),
+            }),
+            None => Ok(Self { node }),
+        }
+    }
+}
+
+/// A state of the nondeterministic finite automaton that a
+/// <code>[Pattern](crate::Pattern)</code> is compiled to.
+#[derive(Debug)]
+enum State {
+    /// Matches one canonicalized grapheme.
+    Literal(String, usize),
+    Any(usize),
+    Class {
+        negated: bool,
+        items: Vec<ClassItem>,
+        next: usize,
+    },
+    Split(usize, usize),
+    Match,
+}
+
+/// A <code>[Pattern](crate::Pattern)</code> compiled for the
+/// <code>[Settings](crate::Settings)</code> of a
+/// <code>[Dictionary](crate::Dictionary)</code>, as an
+/// <code>[Automaton](fst::Automaton)</code> that reads words one grapheme at a time.
+#[derive(Debug)]
+struct Program {
+    states: Vec<State>,
+    start: usize,
+}
+
+/// The state of a <code>[Program](Program)</code>, or
+/// <code>[None](core::option::Option::None)</code> once no word can match.
+type ProgramState = Option<Threads>;
+
+#[derive(Clone, Debug)]
+struct Threads {
+    /// The sorted states of the automaton after the complete graphemes read so far.
+    states: Vec<usize>,
+    /// The bytes of the last grapheme read so far, which the next bytes could still
+    /// extend.
+    pending: Vec<u8>,
+}
+
+impl Program {
+    fn new(pattern: &Pattern, settings: Settings) -> Self {
+        let mut states = vec![State::Match];
+        let start = compile(&pattern.node, 0, settings, &mut states);
+        Self { states, start }
+    }
+
+    /// Adds <code>state</code> to the sorted <code>states</code>, following every
+    /// <code>[Split](State::Split)</code>.
+    fn add(&self, states: &mut Vec<usize>, state: usize) {
+        if let Err(index) = states.binary_search(&state) {
+            states.insert(index, state);
+            if let State::Split(first, second) = self.states[state] {
+                self.add(states, first);
+                self.add(states, second);
+            }
+        }
+    }
+
+    /// Returns the states after <code>grapheme</code>.
+    fn step(&self, states: &[usize], grapheme: &[u8]) -> Vec<usize> {
+        let mut next_states = Vec::new();
+        for &state in states {
+            match self.states[state] {
+                State::Literal(ref literal, next) => {
+                    if literal.as_bytes() == grapheme {
+                        self.add(&mut next_states, next);
+                    }
+                }
+                State::Any(next) => self.add(&mut next_states, next),
+                State::Class {
+                    negated,
+                    ref items,
+                    next,
+                } => {
+                    let grapheme = core::str::from_utf8(grapheme).unwrap_or_default();
+                    let in_class = items.iter().any(|item| match *item {
+                        ClassItem::Grapheme(ref item) => item == grapheme,
+                        ClassItem::Range(low, high) => {
+                            composed_char(grapheme).is_some_and(|ch| low <= ch && ch <= high)
+                        }
+                    });
+                    if in_class != negated {
+                        self.add(&mut next_states, next);
+                    }
+                }
+                State::Split(..) | State::Match => {}
+            }
+        }
+
+        next_states
+    }
+
+    /// Returns whether a word could still match after <code>threads</code>, so that
+    /// the underlying <code>[Fst](fst::raw::Fst)</code> isn't searched past words that
+    /// can't.
+    fn can_continue(&self, threads: &Threads) -> bool {
+        threads
+            .states
+            .iter()
+            .any(|&state| match self.states[state] {
+                State::Literal(ref literal, _) => literal.as_bytes().starts_with(&threads.pending),
+                State::Any(_) | State::Class { .. } => true,
+                State::Match => threads.pending.is_empty(),
+                State::Split(..) => false,
+            })
+    }
+}
+
+impl Automaton for Program {
+    type State = ProgramState;
+
+    #[inline]
+    fn start(&self) -> Self::State {
+        let mut states = Vec::new();
+        self.add(&mut states, self.start);
+        Some(Threads {
+            states,
+            pending: Vec::new(),
+        })
+    }
+
+    fn is_match(&self, state: &Self::State) -> bool {
+        let Some(threads) = state.as_ref() else {
+            return false;
+        };
+        let is_final = |states: &[usize]| {
+            states
+                .iter()
+                .any(|&state| matches!(self.states[state], State::Match))
+        };
+
+        if threads.pending.is_empty() {
+            is_final(&threads.states)
+        } else {
+            core::str::from_utf8(&threads.pending).is_ok()
+                && is_final(&self.step(&threads.states, &threads.pending))
+        }
+    }
+
+    #[inline]
+    fn can_match(&self, state: &Self::State) -> bool {
+        state.is_some()
+    }
+
+    fn accept(&self, state: &Self::State, byte: u8) -> Self::State {
+        let state = state.as_ref()?;
+        let mut pending = Vec::with_capacity(state.pending.len() + 1);
+        pending.extend_from_slice(&state.pending);
+        pending.push(byte);
+
+        // Until the bytes are valid UTF-8, the last character is still incomplete.
+        let split = core::str::from_utf8(&pending)
+            .ok()
+            .and_then(|pending| pending.grapheme_indices(true).nth(1))
+            .map(|(split, _)| split);
+        let threads = match split {
+            None => Threads {
+                states: state.states.clone(),
+                pending,
+            },
+            Some(split) => Threads {
+                states: self.step(&state.states, &pending[..split]),
+                pending: pending[split..].to_vec(),
+            },
+        };
+
+        if self.can_continue(&threads) {
+            Some(threads)
+        } else {
+            None
+        }
+    }
+}
+
+/// Returns the <code>[char](core::primitive::char)</code> that <code>grapheme</code>
+/// composes to in NFC, if it composes to only one.
+#[inline]
+fn composed_char(grapheme: &str) -> Option<char> {
+    let mut chars = grapheme.nfc();
+    let ch = chars.next()?;
+    match chars.next() {
+        None => Some(ch),
+        Some(_) => None,
+    }
+}
+
+/// Adds the states that match <code>node</code> and then continue to
+/// <code>next</code>, returning the first of them.
+fn compile(node: &Node, next: usize, settings: Settings, states: &mut Vec<State>) -> usize {
+    let push = |states: &mut Vec<State>, state| {
+        states.push(state);
+        states.len().wrapping_sub(1)
+    };
+
+    match *node {
+        // Canonicalizing can turn one grapheme into several, such as "ﬃ" into "ffi".
+        Node::Literal(ref literal) => settings
+            .canonicalize(literal)
+            .graphemes(true)
+            .rev()
+            .fold(next, |next, grapheme| {
+                push(states, State::Literal(grapheme.to_string(), next))
+            }),
+        Node::Any => push(states, State::Any(next)),
+        Node::Class { negated, ref items } => {
+            let items = items
+                .iter()
+                .map(|item| match *item {
+                    ClassItem::Grapheme(ref grapheme) => {
+                        ClassItem::Grapheme(settings.canonicalize(grapheme))
+                    }
+                    ClassItem::Range(low, high) => {
+                        let canonicalize = |ch: char| {
+                            composed_char(&settings.canonicalize(ch.encode_utf8(&mut [0; 4])))
+                                .unwrap_or(ch)
+                        };
+                        ClassItem::Range(canonicalize(low), canonicalize(high))
+                    }
+                })
+                .collect();
+            push(
+                states,
+                State::Class {
+                    negated,
+                    items,
+                    next,
+                },
+            )
+        }
+        Node::Concat(ref nodes) => nodes
+            .iter()
+            .rev()
+            .fold(next, |next, node| compile(node, next, settings, states)),
+        Node::Alternation(ref nodes) => {
+            let mut starts = nodes
+                .iter()
+                .map(|node| compile(node, next, settings, states))
+                .collect::<Vec<_>>();
+            let mut start = starts.pop().unwrap_or(next);
+            while let Some(other) = starts.pop() {
+                start = push(states, State::Split(other, start));
+            }
+            start
+        }
+        Node::Star(ref node) => {
+            let split = push(states, State::Match);
+            let body = compile(node, split, settings, states);
+            states[split] = State::Split(body, next);
+            split
+        }
+        Node::Plus(ref node) => {
+            let split = push(states, State::Match);
+            let body = compile(node, split, settings, states);
+            states[split] = State::Split(body, next);
+            body
+        }
+        Node::Question(ref node) => {
+            let body = compile(node, next, settings, states);
+            push(states, State::Split(body, next))
+        }
+    }
+}
+
+/// The <code>[Iterator](core::iter::Iterator)</code> over matching words in byte order
+/// that <code>[Dictionary](crate::Dictionary)::[search](crate::Dictionary::search)</code>
+/// produces.
+pub struct Matches<'d> {
+    stream: Stream<'d, Program>,
+}
+
+impl<'d> Iterator for Matches<'d> {
+    type Item = String;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (word, _) = self.stream.next()?;
+        Some(String::from_utf8_lossy(word).into_owned())
+    }
+}
+
+impl<'d> core::iter::FusedIterator for Matches<'d> {}
+
+impl<D> Dictionary<D>
+where
+    D: AsRef<[u8]>,
+{
+    /// Returns an <code>[Iterator](core::iter::Iterator)</code> over the words in this
+    /// <code>[Dictionary](crate::Dictionary)</code> that match <code>pattern</code> as a
+    /// whole, sorted by their bytes.
+    ///
+    /// The <code>pattern</code> is run as an automaton over the underlying
+    /// <code>[Fst](fst::raw::Fst)</code>, which is only searched past a prefix while a
+    /// word starting with it could still match, so a pattern like <code>ca*</code> is
+    /// much faster than one like <code>*at</code>, which must check every word.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::{CaseFolding, Dictionary, Pattern, Settings};
+    ///
+    /// let settings = Settings::new().with_case_folding(CaseFolding::Full);
+    /// let dictionary = Dictionary::with_settings(["cat", "Coat", "cut", "dot"], settings);
+    ///
+    /// assert_eq!(
+    ///     dictionary
+    ///         .search(&Pattern::glob("C?t").unwrap())
+    ///         .collect::<Vec<_>>(),
+    ///     ["cat", "cut"]
+    /// );
+    /// assert_eq!(
+    ///     dictionary
+    ///         .search(&Pattern::regex("c.+t").unwrap())
+    ///         .collect::<Vec<_>>(),
+    ///     ["cat", "coat", "cut"]
+    /// );
+    /// ```
+    pub fn search(&self, pattern: &Pattern) -> Matches<'_> {
+        Matches {
+            stream: self
+                .fst
+                .search(Program::new(pattern, self.settings))
+                .into_stream(),
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use alloc::vec::Vec;
+use core::fmt;
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use crate::{Dictionary, WordSegmentations, WordSegmentationsWithPayloads};
+
+/// Serializes a <code>[Dictionary](crate::Dictionary)</code> as the bytes that
+/// <code>[Dictionary](crate::Dictionary)::[as_bytes](crate::Dictionary::as_bytes)</code>
+/// returns.
+impl<D> Serialize for Dictionary<D>
+where
+    D: AsRef<[u8]>,
+{
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(self.as_bytes())
+    }
+}
+
+/// Deserializes a <code>[Dictionary](crate::Dictionary)</code> from its bytes, verifying
+/// them as with
+/// <code>[Dictionary](crate::Dictionary)::[from_bytes_verified](crate::Dictionary::from_bytes_verified)</code>.
+///
+/// Formats without a native byte string, such as JSON, are accepted as a sequence of
+/// bytes.
+///
+/// # Examples
+///
+/// ```rust
+/// use wordbreaker::Dictionary;
+///
+/// let dictionary = Dictionary::new(&["just", "ice", "justice"]);
+/// let json = serde_json::to_string(&dictionary).unwrap();
+/// let dictionary: Dictionary<Vec<u8>> = serde_json::from_str(&json).unwrap();
+///
+/// assert!(dictionary.contains("justice"));
+/// ```
+impl<'de> Deserialize<'de> for Dictionary<Vec<u8>> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let bytes = deserializer.deserialize_byte_buf(BytesVisitor)?;
+        Dictionary::from_bytes_verified(bytes).map_err(de::Error::custom)
+    }
+}
+
+/// A <code>[Visitor](serde::de::Visitor)</code> that collects the bytes of a serialized
+/// <code>[Dictionary](crate::Dictionary)</code>.
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("the bytes of a dictionary")
+    }
+
+    #[inline]
+    fn visit_bytes<E>(self, bytes: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(bytes.to_vec())
+    }
+
+    #[inline]
+    fn visit_byte_buf<E>(self, bytes: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(bytes)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+
+        Ok(bytes)
+    }
+}
+
+/// Serializes the remaining segmentations as a sequence of sequences of words, without
+/// advancing the <code>[Iterator](core::iter::Iterator)</code>.
+///
+/// # Examples
+///
+/// ```rust
+/// use wordbreaker::Dictionary;
+///
+/// let dictionary = Dictionary::new(&["just", "ice", "justice"]);
+/// let word_segmentations = dictionary.word_segmentations("justice");
+///
+/// assert_eq!(
+///     serde_json::to_string(&word_segmentations).unwrap(),
+///     r#"[["just","ice"],["justice"]]"#
+/// );
+/// ```
+impl<'s> Serialize for WordSegmentations<'s> {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.clone())
+    }
+}
+
+/// Serializes the remaining segmentations as a sequence of sequences of pairs of words
+/// and payloads, without advancing the <code>[Iterator](core::iter::Iterator)</code>.
+impl<'s> Serialize for WordSegmentationsWithPayloads<'s> {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.clone())
+    }
+}
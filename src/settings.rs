@@ -0,0 +1,302 @@
+use alloc::string::String;
+use caseless::Caseless;
+use unicode_normalization::UnicodeNormalization;
+
+/// How a <code>[Dictionary](crate::Dictionary)</code> treats capitalization.
+///
+/// The same case folding is applied to the words when the
+/// <code>[Dictionary](crate::Dictionary)</code> is built and to every input given to
+/// <code>[Dictionary](crate::Dictionary)::[word_segmentations](crate::Dictionary::word_segmentations)</code>.
+/// Segmentations are still slices of the original input, so their capitalization is
+/// never altered.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum CaseFolding {
+    /// Capitalization is preserved, so the words "Arrow" and "box" will not be a valid
+    /// segmentation of "arrowbox".
+    #[default]
+    Preserve,
+    /// Full Unicode case folding, so the words "Arrow" and "box" will be a valid
+    /// segmentation of "arrowbox" and the word "straße" will match "STRASSE".
+    Full,
+    /// Full Unicode case folding with the Turkish and Azeri mappings of dotted and
+    /// dotless I, so "I" matches "ı" and "İ" matches "i".
+    Turkic,
+}
+
+/// The Unicode normalization form that a <code>[Dictionary](crate::Dictionary)</code>
+/// converts its words and inputs to.
+///
+/// The compatibility forms, <code>[Nfkc](crate::NormalizationForm::Nfkc)</code> and
+/// <code>[Nfkd](crate::NormalizationForm::Nfkd)</code>, make fullwidth Latin letters,
+/// ligatures like "ﬁ" and other compatibility characters match their ordinary
+/// equivalents.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum NormalizationForm {
+    /// Normalization Form C (canonical composition).
+    Nfc,
+    /// Normalization Form D (canonical decomposition).
+    #[default]
+    Nfd,
+    /// Normalization Form KC (compatibility decomposition followed by canonical
+    /// composition).
+    Nfkc,
+    /// Normalization Form KD (compatibility decomposition).
+    Nfkd,
+}
+
+impl NormalizationForm {
+    /// Appends <code>chars</code>, converted to this
+    /// <code>[NormalizationForm](crate::NormalizationForm)</code>, to <code>output</code>.
+    fn normalize_into<I>(self, chars: I, output: &mut String)
+    where
+        I: Iterator<Item = char>,
+    {
+        match self {
+            NormalizationForm::Nfc => output.extend(chars.nfc()),
+            NormalizationForm::Nfd => output.extend(chars.nfd()),
+            NormalizationForm::Nfkc => output.extend(chars.nfkc()),
+            NormalizationForm::Nfkd => output.extend(chars.nfkd()),
+        }
+    }
+
+    /// Returns whether this is a compatibility normalization form.
+    #[inline(always)]
+    fn is_compatibility(self) -> bool {
+        matches!(self, NormalizationForm::Nfkc | NormalizationForm::Nfkd)
+    }
+}
+
+/// What the value stored with each word of a <code>[Dictionary](crate::Dictionary)</code>
+/// means, which decides how values are combined when the same word is added more than
+/// once or <code>[Dictionary](crate::Dictionary)</code>s are combined.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum ValueKind {
+    /// Weights, such as frequencies, which are added together, saturating at
+    /// <code>[u64::MAX](core::primitive::u64::MAX)</code>.
+    #[default]
+    Weights,
+    /// Payloads, such as IDs or tags, which are never added together.
+    Payloads,
+}
+
+/// Settings that control how a <code>[Dictionary](crate::Dictionary)</code> canonicalizes
+/// its words and the inputs it segments, and what the values stored with its words mean.
+///
+/// Settings are chosen when a <code>[Dictionary](crate::Dictionary)</code> is built and
+/// are stored alongside its words, so that
+/// <code>[Dictionary](crate::Dictionary)::[from_bytes](crate::Dictionary::from_bytes)</code>
+/// restores them.
+///
+/// # Examples
+///
+/// ```rust
+/// use wordbreaker::{CaseFolding, Dictionary, Settings};
+///
+/// let settings = Settings::new().with_case_folding(CaseFolding::Full);
+/// let dictionary = Dictionary::with_settings(["Arrow", "box"], settings);
+///
+/// assert_eq!(
+///     dictionary.word_segmentations("ARROWbox").collect::<Vec<_>>(),
+///     [vec!["ARROW", "box"]]
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Settings {
+    case_folding: CaseFolding,
+    normalization_form: NormalizationForm,
+    #[cfg_attr(feature = "serde", serde(default))]
+    value_kind: ValueKind,
+}
+
+impl Settings {
+    /// Creates the default <code>[Settings](crate::Settings)</code>, which preserve
+    /// capitalization, normalize to NFD and store weights.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            case_folding: CaseFolding::Preserve,
+            normalization_form: NormalizationForm::Nfd,
+            value_kind: ValueKind::Weights,
+        }
+    }
+
+    /// Returns these <code>[Settings](crate::Settings)</code> with the given
+    /// <code>[CaseFolding](crate::CaseFolding)</code>.
+    #[inline]
+    pub const fn with_case_folding(mut self, case_folding: CaseFolding) -> Self {
+        self.case_folding = case_folding;
+        self
+    }
+
+    /// Returns these <code>[Settings](crate::Settings)</code> with the given
+    /// <code>[NormalizationForm](crate::NormalizationForm)</code>.
+    #[inline]
+    pub const fn with_normalization_form(mut self, normalization_form: NormalizationForm) -> Self {
+        self.normalization_form = normalization_form;
+        self
+    }
+
+    /// Returns these <code>[Settings](crate::Settings)</code> with the given
+    /// <code>[ValueKind](crate::ValueKind)</code>.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::{DictionaryBuilder, Settings, ValueKind};
+    ///
+    /// let settings = Settings::new().with_value_kind(ValueKind::Payloads);
+    /// let mut builder = DictionaryBuilder::memory(settings);
+    /// builder
+    ///     .extend_unsorted_weighted([("just", 7), ("ice", 2)])
+    ///     .unwrap();
+    /// let dictionary = builder.into_dictionary().unwrap();
+    ///
+    /// assert_eq!(dictionary.settings().value_kind(), ValueKind::Payloads);
+    /// assert_eq!(dictionary.payload("just"), Some(7));
+    /// ```
+    #[inline]
+    pub const fn with_value_kind(mut self, value_kind: ValueKind) -> Self {
+        self.value_kind = value_kind;
+        self
+    }
+
+    /// Returns the <code>[CaseFolding](crate::CaseFolding)</code> of these
+    /// <code>[Settings](crate::Settings)</code>.
+    #[inline(always)]
+    pub const fn case_folding(&self) -> CaseFolding {
+        self.case_folding
+    }
+
+    /// Returns the <code>[NormalizationForm](crate::NormalizationForm)</code> of these
+    /// <code>[Settings](crate::Settings)</code>.
+    #[inline(always)]
+    pub const fn normalization_form(&self) -> NormalizationForm {
+        self.normalization_form
+    }
+
+    /// Returns the <code>[ValueKind](crate::ValueKind)</code> of these
+    /// <code>[Settings](crate::Settings)</code>.
+    #[inline(always)]
+    pub const fn value_kind(&self) -> ValueKind {
+        self.value_kind
+    }
+
+    /// Returns whether these <code>[Settings](crate::Settings)</code> canonicalize text
+    /// the same way as <code>other</code>, whatever their
+    /// <code>[ValueKind](crate::ValueKind)</code>s.
+    #[inline]
+    pub(crate) fn canonicalizes_like(&self, other: &Self) -> bool {
+        self.case_folding == other.case_folding
+            && self.normalization_form == other.normalization_form
+    }
+
+    /// Appends the canonical form of <code>text</code> to <code>output</code>.
+    ///
+    /// Case folding follows the canonical and compatibility caseless matching of the
+    /// Unicode Standard, section 3.13, with the final normalization replaced by this
+    /// <code>[NormalizationForm](crate::NormalizationForm)</code>.
+    pub(crate) fn canonicalize_into(&self, text: &str, output: &mut String) {
+        let form = self.normalization_form;
+        let folded = match self.case_folding {
+            CaseFolding::Preserve => return form.normalize_into(text.chars(), output),
+            CaseFolding::Full => text.nfd().default_case_fold().collect::<String>(),
+            CaseFolding::Turkic => {
+                let mut dotless = String::with_capacity(text.len());
+                let mut chars = text.chars().peekable();
+                while let Some(ch) = chars.next() {
+                    dotless.push(match ch {
+                        '\u{130}' => 'i',
+                        'I' if chars.peek() == Some(&'\u{307}') => {
+                            chars.next();
+                            'i'
+                        }
+                        'I' => '\u{131}',
+                        ch => ch,
+                    });
+                }
+                dotless.nfd().default_case_fold().collect::<String>()
+            }
+        };
+
+        if form.is_compatibility() {
+            form.normalize_into(folded.nfkd().default_case_fold(), output);
+        } else {
+            form.normalize_into(folded.chars(), output);
+        }
+    }
+
+    /// Returns the canonical form of <code>text</code>, as the words of a
+    /// <code>[Dictionary](crate::Dictionary)</code> with these
+    /// <code>[Settings](crate::Settings)</code> are stored.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::{CaseFolding, Settings};
+    ///
+    /// let settings = Settings::new().with_case_folding(CaseFolding::Full);
+    ///
+    /// assert_eq!(settings.canonicalize("Café"), "cafe\u{301}");
+    /// ```
+    #[inline]
+    pub fn canonicalize(&self, text: &str) -> String {
+        let mut output = String::with_capacity(text.len());
+        self.canonicalize_into(text, &mut output);
+        output
+    }
+
+    /// The number of bytes that these <code>[Settings](crate::Settings)</code> occupy at
+    /// the start of a serialized <code>[Dictionary](crate::Dictionary)</code>.
+    pub(crate) const ENCODED_LEN: usize = 3;
+
+    pub(crate) fn encode(&self) -> [u8; Self::ENCODED_LEN] {
+        [
+            match self.case_folding {
+                CaseFolding::Preserve => 0,
+                CaseFolding::Full => 1,
+                CaseFolding::Turkic => 2,
+            },
+            match self.normalization_form {
+                NormalizationForm::Nfc => 0,
+                NormalizationForm::Nfd => 1,
+                NormalizationForm::Nfkc => 2,
+                NormalizationForm::Nfkd => 3,
+            },
+            match self.value_kind {
+                ValueKind::Weights => 0,
+                ValueKind::Payloads => 1,
+            },
+        ]
+    }
+
+    pub(crate) fn decode(bytes: &[u8]) -> Option<Self> {
+        let case_folding = match bytes.first()? {
+            0 => CaseFolding::Preserve,
+            1 => CaseFolding::Full,
+            2 => CaseFolding::Turkic,
+            _ => return None,
+        };
+        let normalization_form = match bytes.get(1)? {
+            0 => NormalizationForm::Nfc,
+            1 => NormalizationForm::Nfd,
+            2 => NormalizationForm::Nfkc,
+            3 => NormalizationForm::Nfkd,
+            _ => return None,
+        };
+        let value_kind = match bytes.get(2)? {
+            0 => ValueKind::Weights,
+            1 => ValueKind::Payloads,
+            _ => return None,
+        };
+
+        Some(Self {
+            case_folding,
+            normalization_form,
+            value_kind,
+        })
+    }
+}
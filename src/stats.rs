@@ -0,0 +1,156 @@
+use alloc::collections::BTreeSet;
+use alloc::string::{String, ToString};
+use fst::Streamer;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::Dictionary;
+
+/// What <code>[Dictionary](crate::Dictionary)::[stats](crate::Dictionary::stats)</code>
+/// reports about the words of a <code>[Dictionary](crate::Dictionary)</code> and its
+/// underlying <code>[Fst](fst::raw::Fst)</code>.
+///
+/// The words are measured in the canonical form they are stored in.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stats {
+    /// The number of words.
+    pub word_count: usize,
+    /// The lengths of the words in bytes.
+    pub byte_lengths: Lengths,
+    /// The lengths of the words in extended grapheme clusters.
+    pub grapheme_lengths: Lengths,
+    /// Every grapheme that appears in a word.
+    pub distinct_graphemes: BTreeSet<String>,
+    /// The number of words that are a single grapheme long, which are the words most
+    /// likely to produce unwanted segmentations.
+    pub single_grapheme_word_count: usize,
+    /// The number of nodes in the underlying <code>[Fst](fst::raw::Fst)</code>.
+    pub fst_node_count: usize,
+    /// The number of bytes in the underlying <code>[Fst](fst::raw::Fst)</code>.
+    pub fst_byte_count: usize,
+}
+
+/// The shortest, longest and mean lengths of the words of a
+/// <code>[Dictionary](crate::Dictionary)</code>, which are all zero when it has no
+/// words.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Lengths {
+    /// The length of the shortest word.
+    pub min: usize,
+    /// The length of the longest word.
+    pub max: usize,
+    /// The mean length of the words.
+    pub mean: f64,
+}
+
+/// Accumulates <code>[Lengths](crate::Lengths)</code> one word at a time.
+#[derive(Default)]
+struct LengthsBuilder {
+    min: Option<usize>,
+    max: usize,
+    total: u128,
+    count: usize,
+}
+
+impl LengthsBuilder {
+    #[inline]
+    fn add(&mut self, length: usize) {
+        self.min = Some(self.min.map_or(length, |min| min.min(length)));
+        self.max = self.max.max(length);
+        self.total = self.total.wrapping_add(length as u128);
+        self.count = self.count.wrapping_add(1);
+    }
+
+    fn finish(self) -> Lengths {
+        Lengths {
+            min: self.min.unwrap_or(0),
+            max: self.max,
+            mean: if self.count == 0 {
+                0.0
+            } else {
+                self.total as f64 / self.count as f64
+            },
+        }
+    }
+}
+
+impl<D> Dictionary<D>
+where
+    D: AsRef<[u8]>,
+{
+    /// Returns <code>[Stats](crate::Stats)</code> about the words of this
+    /// <code>[Dictionary](crate::Dictionary)</code> and its underlying
+    /// <code>[Fst](fst::raw::Fst)</code>, which can help to explain poor segmentations.
+    ///
+    /// This reads every word and visits every node of the
+    /// <code>[Fst](fst::raw::Fst)</code>, so it takes time proportional to the size of
+    /// the <code>[Dictionary](crate::Dictionary)</code>.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::Dictionary;
+    ///
+    /// let dictionary = Dictionary::new(&["a", "just", "ice", "justice", "café"]);
+    /// let stats = dictionary.stats();
+    ///
+    /// assert_eq!(stats.word_count, 5);
+    /// assert_eq!(stats.byte_lengths.max, 7);
+    /// assert_eq!(stats.grapheme_lengths.min, 1);
+    /// assert_eq!(stats.grapheme_lengths.mean, 3.8);
+    /// assert_eq!(stats.single_grapheme_word_count, 1);
+    /// assert!(stats.distinct_graphemes.contains("e\u{301}"));
+    /// ```
+    pub fn stats(&self) -> Stats {
+        let mut byte_lengths = LengthsBuilder::default();
+        let mut grapheme_lengths = LengthsBuilder::default();
+        let mut distinct_graphemes = BTreeSet::new();
+        let mut single_grapheme_word_count = 0_usize;
+
+        let mut stream = self.fst.stream();
+        while let Some((word, _)) = stream.next() {
+            let word = String::from_utf8_lossy(word);
+            let mut grapheme_count = 0_usize;
+            for grapheme in word.graphemes(true) {
+                grapheme_count = grapheme_count.wrapping_add(1);
+                if !distinct_graphemes.contains(grapheme) {
+                    distinct_graphemes.insert(grapheme.to_string());
+                }
+            }
+
+            byte_lengths.add(word.len());
+            grapheme_lengths.add(grapheme_count);
+            if grapheme_count == 1 {
+                single_grapheme_word_count = single_grapheme_word_count.wrapping_add(1);
+            }
+        }
+
+        Stats {
+            word_count: self.fst.len(),
+            byte_lengths: byte_lengths.finish(),
+            grapheme_lengths: grapheme_lengths.finish(),
+            distinct_graphemes,
+            single_grapheme_word_count,
+            fst_node_count: self.fst_node_count(),
+            fst_byte_count: self.fst.as_bytes().len(),
+        }
+    }
+
+    /// Counts the nodes reachable from the root of the underlying
+    /// <code>[Fst](fst::raw::Fst)</code>.
+    fn fst_node_count(&self) -> usize {
+        let root = self.fst.root().addr();
+        let mut visited = BTreeSet::new();
+        visited.insert(root);
+        let mut unvisited = vec![root];
+
+        while let Some(addr) = unvisited.pop() {
+            for transition in self.fst.node(addr).transitions() {
+                if visited.insert(transition.addr) {
+                    unvisited.push(transition.addr);
+                }
+            }
+        }
+
+        visited.len()
+    }
+}
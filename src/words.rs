@@ -0,0 +1,226 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::{Bound, RangeBounds};
+use fst::raw::Stream;
+use fst::Automaton;
+use fst::{IntoStreamer, Streamer};
+use unicode_segmentation::GraphemeCursor;
+
+use crate::Dictionary;
+
+/// The <code>[Iterator](core::iter::Iterator)</code> over words in byte order that
+/// <code>[Dictionary](crate::Dictionary)::[words](crate::Dictionary::words)</code> and
+/// <code>[Dictionary](crate::Dictionary)::[range](crate::Dictionary::range)</code>
+/// produce.
+///
+/// The words are in the canonical form they are stored in, as set by the
+/// <code>[Settings](crate::Settings)</code> of the
+/// <code>[Dictionary](crate::Dictionary)</code>.
+pub struct Words<'d> {
+    stream: Stream<'d>,
+}
+
+impl<'d> Iterator for Words<'d> {
+    type Item = String;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.stream
+            .next()
+            .map(|(word, _)| String::from_utf8_lossy(word).into_owned())
+    }
+}
+
+impl<'d> core::iter::FusedIterator for Words<'d> {}
+
+/// The <code>[Iterator](core::iter::Iterator)</code> over words in byte order that
+/// <code>[Dictionary](crate::Dictionary)::[words_with_prefix](crate::Dictionary::words_with_prefix)</code>
+/// produces.
+pub struct WordsWithPrefix<'d> {
+    stream: Stream<'d, Prefix>,
+    prefix_len: usize,
+    remaining: Option<usize>,
+}
+
+impl<'d> Iterator for WordsWithPrefix<'d> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == Some(0) {
+            return None;
+        }
+
+        while let Some((word, _)) = self.stream.next() {
+            let word = String::from_utf8_lossy(word);
+            // Like `WordSegmentations`, only allow matches that end on a grapheme boundary
+            if GraphemeCursor::new(self.prefix_len, word.len(), true)
+                .is_boundary(&word, 0)
+                .unwrap_or(false)
+            {
+                if let Some(ref mut remaining) = self.remaining {
+                    *remaining = remaining.wrapping_sub(1);
+                }
+                return Some(word.into_owned());
+            }
+        }
+
+        self.remaining = Some(0);
+        None
+    }
+}
+
+impl<'d> core::iter::FusedIterator for WordsWithPrefix<'d> {}
+
+/// An <code>[Automaton](fst::Automaton)</code> that matches the words starting with
+/// a canonicalized prefix, which it owns so that it can outlive the caller's prefix.
+pub(crate) struct Prefix(pub(crate) Vec<u8>);
+
+impl Automaton for Prefix {
+    type State = Option<usize>;
+
+    #[inline]
+    fn start(&self) -> Self::State {
+        Some(0)
+    }
+
+    #[inline]
+    fn is_match(&self, state: &Self::State) -> bool {
+        *state == Some(self.0.len())
+    }
+
+    #[inline]
+    fn can_match(&self, state: &Self::State) -> bool {
+        state.is_some()
+    }
+
+    #[inline]
+    fn will_always_match(&self, state: &Self::State) -> bool {
+        self.is_match(state)
+    }
+
+    #[inline]
+    fn accept(&self, state: &Self::State, byte: u8) -> Self::State {
+        match *state {
+            Some(matched) if matched == self.0.len() => Some(matched),
+            Some(matched) if self.0.get(matched) == Some(&byte) => Some(matched.wrapping_add(1)),
+            _ => None,
+        }
+    }
+}
+
+impl<D> Dictionary<D>
+where
+    D: AsRef<[u8]>,
+{
+    /// Returns an <code>[Iterator](core::iter::Iterator)</code> over the words in this
+    /// <code>[Dictionary](crate::Dictionary)</code>, sorted by their bytes.
+    ///
+    /// This can be used to write the words of a
+    /// <code>[Dictionary](crate::Dictionary)</code> loaded with
+    /// <code>[Dictionary](crate::Dictionary)::[from_bytes](crate::Dictionary::from_bytes)</code>
+    /// back out as a word list.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::Dictionary;
+    ///
+    /// let dictionary = Dictionary::new(&["just", "ice", "justice", "hello"]);
+    ///
+    /// assert_eq!(
+    ///     dictionary.words().collect::<Vec<_>>(),
+    ///     ["hello", "ice", "just", "justice"]
+    /// );
+    /// ```
+    #[inline]
+    pub fn words(&self) -> Words<'_> {
+        Words {
+            stream: self.fst.stream(),
+        }
+    }
+
+    /// Returns an <code>[Iterator](core::iter::Iterator)</code> over the words in this
+    /// <code>[Dictionary](crate::Dictionary)</code> that are within <code>range</code>,
+    /// sorted by their bytes.
+    ///
+    /// The bounds of the <code>range</code> are canonicalized according to the
+    /// <code>[Settings](crate::Settings)</code> of this
+    /// <code>[Dictionary](crate::Dictionary)</code> before comparing them to its words.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::Dictionary;
+    ///
+    /// let dictionary = Dictionary::new(&["just", "ice", "justice", "hello"]);
+    ///
+    /// assert_eq!(
+    ///     dictionary.range("i"..="just").collect::<Vec<_>>(),
+    ///     ["ice", "just"]
+    /// );
+    /// assert_eq!(dictionary.range("j"..).collect::<Vec<_>>(), ["just", "justice"]);
+    /// ```
+    pub fn range<R, S>(&self, range: R) -> Words<'_>
+    where
+        R: RangeBounds<S>,
+        S: AsRef<str>,
+    {
+        let mut builder = self.fst.range();
+        builder = match range.start_bound() {
+            Bound::Included(start) => builder.ge(self.settings.canonicalize(start.as_ref())),
+            Bound::Excluded(start) => builder.gt(self.settings.canonicalize(start.as_ref())),
+            Bound::Unbounded => builder,
+        };
+        builder = match range.end_bound() {
+            Bound::Included(end) => builder.le(self.settings.canonicalize(end.as_ref())),
+            Bound::Excluded(end) => builder.lt(self.settings.canonicalize(end.as_ref())),
+            Bound::Unbounded => builder,
+        };
+
+        Words {
+            stream: builder.into_stream(),
+        }
+    }
+
+    /// Returns an <code>[Iterator](core::iter::Iterator)</code> over at most
+    /// <code>limit</code> words in this <code>[Dictionary](crate::Dictionary)</code>
+    /// that start with <code>prefix</code>, sorted by their bytes. With a
+    /// <code>limit</code> of <code>[None](core::option::Option::None)</code>, every such
+    /// word is returned.
+    ///
+    /// The <code>prefix</code> is canonicalized according to the
+    /// <code>[Settings](crate::Settings)</code> of this
+    /// <code>[Dictionary](crate::Dictionary)</code>, and it must end on a grapheme
+    /// boundary of the word, so the prefix "cafe" does not match "café" even when it is
+    /// stored in NFD form.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wordbreaker::Dictionary;
+    ///
+    /// let dictionary = Dictionary::new(&["café", "cafeteria", "cab", "just", "justice"]);
+    ///
+    /// assert_eq!(
+    ///     dictionary.words_with_prefix("ca", None).collect::<Vec<_>>(),
+    ///     ["cab", "cafeteria", "cafe\u{301}"]
+    /// );
+    /// assert_eq!(
+    ///     dictionary.words_with_prefix("cafe", None).collect::<Vec<_>>(),
+    ///     ["cafeteria"]
+    /// );
+    /// assert_eq!(
+    ///     dictionary.words_with_prefix("just", Some(1)).collect::<Vec<_>>(),
+    ///     ["just"]
+    /// );
+    /// ```
+    pub fn words_with_prefix(&self, prefix: &str, limit: Option<usize>) -> WordsWithPrefix<'_> {
+        let prefix = self.settings.canonicalize(prefix);
+
+        WordsWithPrefix {
+            prefix_len: prefix.len(),
+            stream: self.fst.search(Prefix(prefix.into_bytes())).into_stream(),
+            remaining: limit,
+        }
+    }
+}